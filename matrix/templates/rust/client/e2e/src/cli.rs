@@ -0,0 +1,167 @@
+//! Command-line interface.
+
+use std::{path::PathBuf, time::Duration};
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+use crate::runner::Filter;
+
+/// End-to-end MCP client for the MCPBench interop matrix.
+///
+/// Without a subcommand, behaves like `run`.
+#[derive(Debug, Parser)]
+#[command(version, args_conflicts_with_subcommands = true)]
+pub struct Cli {
+    /// More log output: `-v` for debug, `-vv` for trace. `RUST_LOG` takes precedence.
+    #[arg(short, long, action = clap::ArgAction::Count, global = true)]
+    pub verbose: u8,
+
+    /// Only log warnings and errors.
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    pub quiet: bool,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    #[command(flatten)]
+    pub run: RunArgs,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Connect to a server and run the e2e scenarios.
+    Run(RunArgs),
+    /// Print the scenarios `run` would execute, without connecting.
+    ListScenarios(FilterArgs),
+    /// Connect, print what the server advertises as JSON, and disconnect.
+    Probe(ConnectArgs),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TransportKind {
+    /// Streamable HTTP (2025-03-26 and later).
+    StreamableHttp,
+    /// Spawn the server command given after `--` and talk to it over stdin/stdout.
+    Stdio,
+    /// Legacy HTTP+SSE (2024-11-05); `SERVER_URL` is the SSE endpoint, e.g. `http://localhost:8000/sse`.
+    Sse,
+    /// Try streamable HTTP and fall back to HTTP+SSE if `SERVER_URL` rejects the POST with a 4xx.
+    Auto,
+}
+
+impl TransportKind {
+    /// The command-line spelling, also used in reports.
+    pub fn name(self) -> &'static str {
+        match self {
+            TransportKind::StreamableHttp => "streamable-http",
+            TransportKind::Stdio => "stdio",
+            TransportKind::Sse => "sse",
+            TransportKind::Auto => "auto",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct ConnectArgs {
+    /// MCP endpoint URL; ignored with `--transport stdio`.
+    #[arg(default_value = "http://localhost:8000/mcp", env = "MCP_SERVER_URL")]
+    pub server_url: String,
+
+    /// Server command for `--transport stdio`, e.g. `-- python server.py`.
+    #[arg(last = true, value_name = "COMMAND")]
+    pub server_command: Vec<String>,
+
+    /// Transport used to reach the server.
+    #[arg(long, value_enum, default_value_t = TransportKind::StreamableHttp)]
+    pub transport: TransportKind,
+
+    /// Protocol version to request in `initialize`, e.g. `2025-03-26`. Defaults to `2025-06-18`.
+    #[arg(long, value_name = "VERSION")]
+    pub protocol_version: Option<String>,
+
+    /// Seconds to wait for any single request before failing it.
+    #[arg(long, value_name = "SECS", default_value_t = 30)]
+    pub request_timeout: u64,
+}
+
+impl ConnectArgs {
+    pub fn request_timeout(&self) -> Duration {
+        Duration::from_secs(self.request_timeout)
+    }
+
+    /// The server as shown in logs and reports.
+    pub fn target(&self) -> String {
+        match self.transport {
+            TransportKind::Stdio => self.server_command.join(" "),
+            TransportKind::StreamableHttp | TransportKind::Sse | TransportKind::Auto => self.server_url.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct FilterArgs {
+    /// Only run scenarios matching this name; `*` is a wildcard. Repeatable.
+    #[arg(short, long = "include", value_name = "PATTERN")]
+    pub include: Vec<String>,
+
+    /// Skip scenarios matching this name; `*` is a wildcard. Repeatable.
+    #[arg(short = 'x', long = "exclude", value_name = "PATTERN")]
+    pub exclude: Vec<String>,
+}
+
+impl FilterArgs {
+    pub fn filter(&self) -> Filter {
+        Filter {
+            include: self.include.clone(),
+            exclude: self.exclude.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct RunArgs {
+    #[command(flatten)]
+    pub connect: ConnectArgs,
+
+    #[command(flatten)]
+    pub filter: FilterArgs,
+
+    /// Seconds a whole scenario may take before it is failed.
+    #[arg(long, value_name = "SECS", default_value_t = 120)]
+    pub scenario_timeout: u64,
+
+    /// Fail the `transport_negotiation` scenario unless this transport was the one negotiated.
+    #[arg(long, value_enum, value_name = "TRANSPORT")]
+    pub expect_transport: Option<TransportKind>,
+
+    /// Number of `generated_tool_NNN` tools the server was started with, so
+    /// `tools_list` can tell a truncated listing from a complete one.
+    #[arg(long, value_name = "N", env = "MCP_SERVER_GENERATED_TOOLS")]
+    pub expect_generated_tools: Option<usize>,
+
+    /// Write a JSON report to this path.
+    #[arg(long, value_name = "PATH")]
+    pub json: Option<PathBuf>,
+
+    /// Write a JUnit XML report to this path.
+    #[arg(long, value_name = "PATH")]
+    pub junit: Option<PathBuf>,
+}
+
+impl RunArgs {
+    pub fn scenario_timeout(&self) -> Duration {
+        Duration::from_secs(self.scenario_timeout)
+    }
+}
+
+impl Cli {
+    /// The default `tracing` filter for the chosen verbosity.
+    pub fn log_filter(&self) -> String {
+        match (self.quiet, self.verbose) {
+            (true, _) => "warn".to_string(),
+            (false, 0) => format!("info,{}=debug", env!("CARGO_CRATE_NAME")),
+            (false, 1) => "debug".to_string(),
+            (false, _) => "trace".to_string(),
+        }
+    }
+}
@@ -0,0 +1,223 @@
+//! Client-side handler answering server-initiated requests during E2E runs.
+
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex, RwLock},
+};
+
+use tokio::sync::watch;
+
+use rmcp::{
+    ClientHandler, ErrorData as McpError, RoleClient,
+    model::{
+        ClientInfo, Content, CreateElicitationRequestParam, CreateElicitationResult, CreateMessageRequestParam,
+        CreateMessageResult, ElicitationAction, ListRootsResult, LoggingMessageNotificationParam,
+        ProgressNotificationParam, ProgressToken, RequestId, Role, Root, SamplingMessage,
+    },
+    service::{NotificationContext, RequestContext},
+};
+
+use crate::observed::ResponseLog;
+
+/// Model name reported by the fake sampling backend.
+pub const FAKE_MODEL: &str = "mcpbench-fake-llm";
+
+/// Deterministic reply produced by the fake LLM for a given user prompt.
+pub fn fake_completion(prompt: &str) -> String {
+    format!("Fake LLM response to: {prompt}")
+}
+
+/// Roots advertised when `MCP_CLIENT_ROOTS` is not set.
+pub const DEFAULT_ROOTS: &[&str] = &["file:///tmp/mcpbench/workspace", "file:///tmp/mcpbench/data"];
+
+/// Builds roots from `file://` URIs, naming each after its last path segment.
+pub fn roots_from_uris<I, S>(uris: I) -> Vec<Root>
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    uris.into_iter()
+        .map(Into::into)
+        .map(|uri| Root {
+            name: uri.rsplit('/').find(|s| !s.is_empty()).map(str::to_string),
+            uri,
+        })
+        .collect()
+}
+
+/// Scripted answer to the next `elicitation/create` request.
+#[derive(Debug, Clone)]
+pub enum ElicitationReply {
+    Accept(serde_json::Value),
+    Decline,
+    Cancel,
+}
+
+#[derive(Clone)]
+pub struct E2eClient {
+    info: ClientInfo,
+    roots: Arc<RwLock<Vec<Root>>>,
+    elicitation_replies: Arc<Mutex<VecDeque<ElicitationReply>>>,
+    progress: Arc<Mutex<Vec<ProgressNotificationParam>>>,
+    log_messages: Arc<Mutex<Vec<LoggingMessageNotificationParam>>>,
+    tool_list_changed: Arc<watch::Sender<usize>>,
+    responses: ResponseLog,
+}
+
+impl E2eClient {
+    pub fn new(info: ClientInfo, roots: Vec<Root>) -> Self {
+        Self {
+            info,
+            roots: Arc::new(RwLock::new(roots)),
+            elicitation_replies: Arc::default(),
+            progress: Arc::default(),
+            log_messages: Arc::default(),
+            tool_list_changed: Arc::new(watch::Sender::new(0)),
+            responses: ResponseLog::default(),
+        }
+    }
+
+    pub fn roots(&self) -> Vec<Root> {
+        self.roots.read().expect("roots lock poisoned").clone()
+    }
+
+    /// Replaces the advertised roots. Callers are responsible for sending
+    /// `notifications/roots/list_changed` afterwards.
+    pub fn set_roots(&self, roots: Vec<Root>) {
+        *self.roots.write().expect("roots lock poisoned") = roots;
+    }
+
+    /// Queues the reply for the next elicitation request. Requests arriving
+    /// with nothing queued are declined.
+    pub fn script_elicitation(&self, reply: ElicitationReply) {
+        self.elicitation_replies
+            .lock()
+            .expect("elicitation lock poisoned")
+            .push_back(reply);
+    }
+
+    /// Progress notifications received so far for `token`, in arrival order.
+    pub fn progress_for(&self, token: &ProgressToken) -> Vec<ProgressNotificationParam> {
+        self.progress
+            .lock()
+            .expect("progress lock poisoned")
+            .iter()
+            .filter(|p| &p.progress_token == token)
+            .cloned()
+            .collect()
+    }
+
+    /// Drains the `notifications/message` received since the last call.
+    pub fn take_log_messages(&self) -> Vec<LoggingMessageNotificationParam> {
+        std::mem::take(&mut *self.log_messages.lock().expect("log lock poisoned"))
+    }
+
+    /// The log an [`Observed`](crate::observed::Observed) transport fills for this handler.
+    pub fn response_log(&self) -> ResponseLog {
+        self.responses.clone()
+    }
+
+    /// Whether the server sent a response or error for `id`, even one rmcp
+    /// discarded because the request had been cancelled.
+    pub fn received_response(&self, id: &RequestId) -> bool {
+        self.responses.contains(id)
+    }
+
+    /// Watches the number of `notifications/tools/list_changed` received.
+    pub fn subscribe_tool_list_changed(&self) -> watch::Receiver<usize> {
+        self.tool_list_changed.subscribe()
+    }
+}
+
+impl ClientHandler for E2eClient {
+    async fn create_message(
+        &self,
+        params: CreateMessageRequestParam,
+        _context: RequestContext<RoleClient>,
+    ) -> Result<CreateMessageResult, McpError> {
+        let prompt = params
+            .messages
+            .iter()
+            .rev()
+            .find(|m| m.role == Role::User)
+            .and_then(|m| m.content.as_text())
+            .map(|t| t.text.clone())
+            .ok_or_else(|| McpError::invalid_params("sampling request has no user text message", None))?;
+
+        tracing::info!("Sampling request received: {prompt:?}");
+
+        Ok(CreateMessageResult {
+            model: FAKE_MODEL.to_string(),
+            stop_reason: Some(CreateMessageResult::STOP_REASON_END_TURN.to_string()),
+            message: SamplingMessage {
+                role: Role::Assistant,
+                content: Content::text(fake_completion(&prompt)),
+            },
+        })
+    }
+
+    async fn list_roots(
+        &self,
+        _context: RequestContext<RoleClient>,
+    ) -> Result<ListRootsResult, McpError> {
+        let roots = self.roots();
+        tracing::info!("roots/list request received, returning {} roots", roots.len());
+        Ok(ListRootsResult { roots })
+    }
+
+    async fn create_elicitation(
+        &self,
+        request: CreateElicitationRequestParam,
+        _context: RequestContext<RoleClient>,
+    ) -> Result<CreateElicitationResult, McpError> {
+        let reply = self
+            .elicitation_replies
+            .lock()
+            .expect("elicitation lock poisoned")
+            .pop_front()
+            .unwrap_or(ElicitationReply::Decline);
+        tracing::info!("Elicitation request received: {:?}, replying {reply:?}", request.message);
+
+        Ok(match reply {
+            ElicitationReply::Accept(content) => CreateElicitationResult {
+                action: ElicitationAction::Accept,
+                content: Some(content),
+            },
+            ElicitationReply::Decline => CreateElicitationResult {
+                action: ElicitationAction::Decline,
+                content: None,
+            },
+            ElicitationReply::Cancel => CreateElicitationResult {
+                action: ElicitationAction::Cancel,
+                content: None,
+            },
+        })
+    }
+
+    async fn on_progress(
+        &self,
+        params: ProgressNotificationParam,
+        _context: NotificationContext<RoleClient>,
+    ) {
+        tracing::debug!("Progress notification: {params:?}");
+        self.progress.lock().expect("progress lock poisoned").push(params);
+    }
+
+    async fn on_logging_message(
+        &self,
+        params: LoggingMessageNotificationParam,
+        _context: NotificationContext<RoleClient>,
+    ) {
+        tracing::debug!("Server log [{:?}]: {}", params.level, params.data);
+        self.log_messages.lock().expect("log lock poisoned").push(params);
+    }
+
+    async fn on_tool_list_changed(&self, _context: NotificationContext<RoleClient>) {
+        tracing::info!("tools/list_changed notification received");
+        self.tool_list_changed.send_modify(|count| *count += 1);
+    }
+
+    fn get_info(&self) -> ClientInfo {
+        self.info.clone()
+    }
+}
@@ -1,112 +1,299 @@
+mod cli;
+mod handler;
+mod observed;
+mod process;
+mod report;
+mod runner;
+mod scenarios;
+mod session;
+mod transport;
+
 use anyhow::Result;
+use clap::Parser;
 use rmcp::{
-    ServiceExt,
-    model::{CallToolRequestParam, ClientCapabilities, ClientInfo, Implementation},
-    transport::StreamableHttpClientTransport,
+    RoleClient, ServiceExt,
+    model::{ClientCapabilities, ClientInfo, Implementation, ProtocolVersion, ServerInfo},
+    service::RunningService,
+    transport::{SseClientTransport, StreamableHttpClientTransport},
 };
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
-use std::env;
+use std::{
+    env,
+    process::ExitCode,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use cli::{Cli, Command, ConnectArgs, FilterArgs, RunArgs, TransportKind};
+use handler::E2eClient;
+use observed::Observed;
+use process::ServerProcess;
+use report::Report;
+use runner::{Context, ScenarioResult, Status};
 
 #[tokio::main]
-async fn main() -> Result<()> {
-    // Initialize logging
+async fn main() -> Result<ExitCode> {
+    let cli = Cli::parse();
+
+    // Initialize logging; logs go to stderr so `probe` and `list-scenarios` output can be piped
     tracing_subscriber::registry()
         .with(
             tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| format!("info,{}=debug", env!("CARGO_CRATE_NAME")).into()),
+                .unwrap_or_else(|_| cli.log_filter().into()),
         )
-        .with(tracing_subscriber::fmt::layer())
+        .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
         .init();
 
-    // Get server URL from command line args or use default
-    let server_url = env::args()
-        .nth(1)
-        .unwrap_or_else(|| "http://localhost:8000/mcp".to_string());
-
-    tracing::info!("Connecting to MCP server at {}...", server_url);
+    match cli.command {
+        Some(Command::Run(args)) => run(args).await,
+        Some(Command::ListScenarios(args)) => Ok(list_scenarios(&args)),
+        Some(Command::Probe(args)) => probe(args).await,
+        None => run(cli.run).await,
+    }
+}
 
-    let transport = StreamableHttpClientTransport::from_uri(&*server_url);
-    let client_info = ClientInfo {
-        protocol_version: Default::default(),
-        capabilities: ClientCapabilities::default(),
+fn client_info(args: &ConnectArgs) -> Result<ClientInfo> {
+    let protocol_version = match &args.protocol_version {
+        Some(version) => parse_protocol_version(version)?,
+        // rmcp's default is older than the newest revision the scenarios cover
+        None => ProtocolVersion::V_2025_06_18,
+    };
+    Ok(ClientInfo {
+        protocol_version,
+        capabilities: ClientCapabilities::builder()
+            .enable_roots()
+            .enable_roots_list_changed()
+            .enable_sampling()
+            .enable_elicitation()
+            .build(),
         client_info: Implementation {
             name: "MCP Rust E2E Client".to_string(),
+            title: None,
             version: "0.1.0".to_string(),
+            icons: None,
+            website_url: None,
         },
+    })
+}
+
+/// Accepts any string, so malformed versions can be sent on purpose.
+fn parse_protocol_version(version: &str) -> Result<ProtocolVersion> {
+    Ok(serde_json::from_value(serde_json::Value::String(version.to_string()))?)
+}
+
+/// How long a spawned server gets to exit after its stdin closes.
+const SERVER_EXIT_GRACE: Duration = Duration::from_secs(5);
+
+/// Opens the configured transport and performs `initialize`, returning the
+/// session and the transport actually used. A spawned server is stored in
+/// `process` before initializing, so its stderr is available even when the
+/// handshake fails.
+async fn connect(
+    args: &ConnectArgs,
+    client_info: ClientInfo,
+    process: &mut Option<ServerProcess>,
+) -> Result<(RunningService<RoleClient, E2eClient>, TransportKind)> {
+    // Roots can be overridden with a comma-separated list of file:// URIs
+    let roots = match env::var("MCP_CLIENT_ROOTS") {
+        Ok(value) => handler::roots_from_uris(value.split(',').map(str::trim).filter(|s| !s.is_empty())),
+        Err(_) => handler::roots_from_uris(handler::DEFAULT_ROOTS.iter().copied()),
+    };
+    let transport = match args.transport {
+        TransportKind::Auto => transport::negotiate(&args.server_url, args.request_timeout()).await?,
+        kind => kind,
     };
+    let handler = E2eClient::new(client_info, roots);
 
-    let client = client_info.serve(transport).await.inspect_err(|e| {
-        tracing::error!("Client error: {:?}", e);
-    })?;
+    tracing::info!("Connecting to MCP server at {} ({})...", args.target(), transport.name());
+    let client = match transport {
+        TransportKind::StreamableHttp => {
+            let transport = StreamableHttpClientTransport::from_uri(&*args.server_url);
+            let transport = Observed::new(transport, handler.response_log());
+            tokio::time::timeout(args.request_timeout(), handler.serve(transport)).await
+        }
+        TransportKind::Stdio => {
+            let (transport, spawned) = process::spawn(&args.server_command)?;
+            *process = Some(spawned);
+            let transport = Observed::new(transport, handler.response_log());
+            tokio::time::timeout(args.request_timeout(), handler.serve(transport)).await
+        }
+        TransportKind::Sse => {
+            // The legacy transport needs the endpoint event from `GET` before `initialize` can be posted
+            let start = SseClientTransport::start(args.server_url.clone());
+            let transport = tokio::time::timeout(args.request_timeout(), start)
+                .await
+                .map_err(|_| anyhow::anyhow!("no SSE endpoint event within {:?}", args.request_timeout()))??;
+            let transport = Observed::new(transport, handler.response_log());
+            tokio::time::timeout(args.request_timeout(), handler.serve(transport)).await
+        }
+        TransportKind::Auto => unreachable!("resolved above"),
+    };
+    let client = client
+        .map_err(|_| anyhow::anyhow!("initialize did not complete within {:?}", args.request_timeout()))?
+        .inspect_err(|e| tracing::error!("Client error: {:?}", e))?;
 
     tracing::info!("✓ Connected to MCP server");
+    Ok((client, transport))
+}
+
+async fn run(args: RunArgs) -> Result<ExitCode> {
+    let filter = args.filter.filter();
+    let all = scenarios::all();
+    let selected: Vec<_> = all.iter().filter(|s| filter.matches(s.name)).collect();
+    anyhow::ensure!(!selected.is_empty(), "no scenario matches the include/exclude filters");
+
+    let started = Instant::now();
+    let client_info = client_info(&args.connect)?;
+    let implementation = client_info.client_info.clone();
+
+    let mut process = None;
+    let (client, transport) = match connect(&args.connect, client_info, &mut process).await {
+        Ok(connected) => connected,
+        Err(e) => {
+            let results = [setup_failure("connect", started.elapsed(), format!("{e:#}"))];
+            let mut report = Report::new(implementation, &args.connect, None, None, started.elapsed(), &results);
+            if let Some(mut process) = process {
+                process.shutdown(SERVER_EXIT_GRACE).await;
+                report.server_stderr = Some(process.stderr());
+            }
+            write_reports(&args, report)?;
+            return Ok(ExitCode::FAILURE);
+        }
+    };
 
     // Initialize
-    let server_info = client.peer_info();
+    let server_info: Option<ServerInfo> = client.peer_info().cloned();
     tracing::info!("Server info: {server_info:#?}");
 
-    tracing::info!("\n=== MCP E2E Test Started ===");
+    let request_timeout = args.connect.request_timeout();
+    let negotiations = Arc::default();
+    let listing = tokio::time::timeout(request_timeout, scenarios::list_all_tool_pages(&client))
+        .await
+        .unwrap_or_else(|_| Err(anyhow::anyhow!("tools/list did not complete within {request_timeout:?}")));
+    let results = match listing {
+        Ok((tools, tool_pages)) => {
+            let ctx = Context {
+                client: client.peer().clone(),
+                handler: client.service().clone(),
+                server_info: server_info.clone(),
+                tools: Arc::new(tools),
+                tool_pages,
+                request_timeout,
+                transport,
+                expected_generated_tools: args.expect_generated_tools,
+                expected_transport: args.expect_transport,
+                connect: Arc::new(ConnectArgs {
+                    transport,
+                    ..args.connect.clone()
+                }),
+                negotiations: Arc::clone(&negotiations),
+            };
 
-    // 1. List available tools
-    tracing::info!("\n1. Listing available tools...");
-    let tools = client.list_tools(Default::default()).await?;
-    tracing::info!("Available tools:");
-    for tool in &tools.tools {
-        tracing::info!("  - {}: {}", tool.name, tool.description.as_deref().unwrap_or("No description"));
-        tracing::info!("    Schema: {}", serde_json::to_string_pretty(&tool.input_schema).unwrap());
+            tracing::info!("\n=== MCP E2E Test Started ===");
+            runner::run(&selected, &ctx, args.scenario_timeout()).await
+        }
+        Err(e) => {
+            tracing::error!("Listing tools failed: {e:#}");
+            vec![setup_failure("tools_list", started.elapsed(), format!("{e:#}"))]
+        }
+    };
+    let passed = runner::summarize(&results);
+
+    // Cleanup
+    client.cancel().await?;
+    tracing::info!("✓ Disconnected from MCP server");
+
+    let mut report = Report::new(
+        implementation,
+        &args.connect,
+        Some(transport),
+        server_info.as_ref(),
+        started.elapsed(),
+        &results,
+    );
+    report.protocol_negotiation = std::mem::take(&mut *negotiations.lock().unwrap());
+    if let Some(mut process) = process {
+        process.shutdown(SERVER_EXIT_GRACE).await;
+        report.server_stderr = Some(process.stderr());
     }
+    write_reports(&args, report)?;
 
-    // 2. Test send_message tool if available
-    if tools.tools.iter().any(|t| t.name == "send_message") {
-        tracing::info!("\n2. Testing send_message tool...");
-        
-        let tool_result = client
-            .call_tool(CallToolRequestParam {
-                name: "send_message".into(),
-                arguments: serde_json::json!({
-                    "message": "Hello from Rust E2E client!"
-                }).as_object().cloned(),
-            })
-            .await?;
-
-        tracing::info!("Tool result: {tool_result:#?}");
+    if passed {
+        tracing::info!("\n✓ All E2E tests completed successfully!");
+        Ok(ExitCode::SUCCESS)
+    } else {
+        tracing::error!("\n✗ E2E tests failed");
+        Ok(ExitCode::FAILURE)
     }
+}
 
-    // 3. Test get_server_info tool if available
-    if tools.tools.iter().any(|t| t.name == "get_server_info") {
-        tracing::info!("\n3. Testing get_server_info tool...");
-        
-        let tool_result = client
-            .call_tool(CallToolRequestParam {
-                name: "get_server_info".into(),
-                arguments: serde_json::json!({}).as_object().cloned(),
-            })
-            .await?;
-
-        tracing::info!("Tool result: {tool_result:#?}");
+fn list_scenarios(args: &FilterArgs) -> ExitCode {
+    let filter = args.filter();
+    for scenario in scenarios::all().iter().filter(|s| filter.matches(s.name)) {
+        println!("{:<22} {}", scenario.name, scenario.description);
     }
+    ExitCode::SUCCESS
+}
+
+/// Prints the server's `initialize` result and what it lists, as JSON on stdout.
+async fn probe(args: ConnectArgs) -> Result<ExitCode> {
+    let mut process = None;
+    let (client, transport) = connect(&args, client_info(&args)?, &mut process).await?;
+    let Some(info) = client.peer_info().cloned() else {
+        anyhow::bail!("server did not return initialize information");
+    };
 
-    // 4. Test increment tool if available (from TypeScript server)
-    if tools.tools.iter().any(|t| t.name == "increment") {
-        tracing::info!("\n4. Testing increment tool...");
-        
-        let tool_result = client
-            .call_tool(CallToolRequestParam {
-                name: "increment".into(),
-                arguments: serde_json::json!({
-                    "value": 42
-                }).as_object().cloned(),
-            })
-            .await?;
-
-        tracing::info!("Tool result: {tool_result:#?}");
+    let timeout = args.request_timeout();
+    let mut listed = serde_json::Map::new();
+    if info.capabilities.tools.is_some() {
+        let (tools, _) = tokio::time::timeout(timeout, scenarios::list_all_tool_pages(&client)).await??;
+        listed.insert("tools".into(), tools.iter().map(|t| t.name.clone()).collect());
+    }
+    if info.capabilities.resources.is_some() {
+        let resources = tokio::time::timeout(timeout, client.list_all_resources()).await??;
+        listed.insert("resources".into(), resources.iter().map(|r| r.uri.clone()).collect());
+    }
+    if info.capabilities.prompts.is_some() {
+        let prompts = tokio::time::timeout(timeout, client.list_all_prompts()).await??;
+        listed.insert("prompts".into(), prompts.iter().map(|p| p.name.clone()).collect());
     }
 
-    tracing::info!("\n✓ All E2E tests completed successfully!");
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&serde_json::json!({
+            "server": args.target(),
+            "transport": transport.name(),
+            "initialize": info,
+            "listed": listed,
+        }))?
+    );
 
-    // Cleanup
     client.cancel().await?;
-    tracing::info!("✓ Disconnected from MCP server");
+    if let Some(mut process) = process {
+        process.shutdown(SERVER_EXIT_GRACE).await;
+    }
+    Ok(ExitCode::SUCCESS)
+}
 
+/// A failure before any scenario could run, recorded so reports are still written.
+fn setup_failure(name: &'static str, duration: Duration, detail: String) -> ScenarioResult {
+    ScenarioResult {
+        name,
+        description: "Setting up the session",
+        status: Status::Failed,
+        duration,
+        detail: Some(detail),
+    }
+}
+
+fn write_reports(args: &RunArgs, report: Report) -> Result<()> {
+    if let Some(path) = &args.json {
+        report.write_json(path)?;
+        tracing::info!("JSON report written to {}", path.display());
+    }
+    if let Some(path) = &args.junit {
+        report.write_junit(path)?;
+        tracing::info!("JUnit report written to {}", path.display());
+    }
     Ok(())
-} 
\ No newline at end of file
+}
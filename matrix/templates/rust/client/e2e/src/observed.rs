@@ -0,0 +1,70 @@
+//! A transport wrapper that records which requests the server answered.
+//!
+//! rmcp forgets a request as soon as the client cancels it and silently drops
+//! any response that arrives for it afterwards, so the only place a late
+//! response is visible is the transport.
+
+use std::{
+    collections::HashSet,
+    future::Future,
+    sync::{Arc, Mutex},
+};
+
+use rmcp::{
+    RoleClient,
+    model::{JsonRpcMessage, RequestId},
+    service::{RxJsonRpcMessage, TxJsonRpcMessage},
+    transport::Transport,
+};
+
+/// Ids of every response and error response received, shared between the
+/// transport that fills it and the handler that scenarios query.
+#[derive(Clone, Default)]
+pub struct ResponseLog(Arc<Mutex<HashSet<RequestId>>>);
+
+impl ResponseLog {
+    pub fn contains(&self, id: &RequestId) -> bool {
+        self.0.lock().expect("response log lock poisoned").contains(id)
+    }
+
+    fn record(&self, id: RequestId) {
+        self.0.lock().expect("response log lock poisoned").insert(id);
+    }
+}
+
+/// Wraps a client transport and records response ids into a [`ResponseLog`].
+pub struct Observed<T> {
+    inner: T,
+    responses: ResponseLog,
+}
+
+impl<T> Observed<T> {
+    pub fn new(inner: T, responses: ResponseLog) -> Self {
+        Self { inner, responses }
+    }
+}
+
+impl<T: Transport<RoleClient>> Transport<RoleClient> for Observed<T> {
+    type Error = T::Error;
+
+    fn send(
+        &mut self,
+        item: TxJsonRpcMessage<RoleClient>,
+    ) -> impl Future<Output = Result<(), Self::Error>> + Send + 'static {
+        self.inner.send(item)
+    }
+
+    async fn receive(&mut self) -> Option<RxJsonRpcMessage<RoleClient>> {
+        let message = self.inner.receive().await;
+        match &message {
+            Some(JsonRpcMessage::Response(response)) => self.responses.record(response.id.clone()),
+            Some(JsonRpcMessage::Error(error)) => self.responses.record(error.id.clone()),
+            _ => {}
+        }
+        message
+    }
+
+    fn close(&mut self) -> impl Future<Output = Result<(), Self::Error>> + Send {
+        self.inner.close()
+    }
+}
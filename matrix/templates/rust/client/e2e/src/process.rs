@@ -0,0 +1,147 @@
+//! Spawning a server as a child process for the stdio transport.
+//!
+//! The child is started in its own process group so that wrappers such as
+//! `npx` or `uv run` can be torn down together with the server they launch.
+
+use std::{
+    collections::VecDeque,
+    process::Stdio,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use anyhow::{Context as _, Result};
+use rmcp::transport::TokioChildProcess;
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+/// Stderr lines kept for the report; older lines are dropped first.
+const MAX_STDERR_LINES: usize = 2000;
+
+/// A spawned server. Dropping it kills the whole process group.
+pub struct ServerProcess {
+    pid: Option<u32>,
+    stderr: Arc<Mutex<StderrLog>>,
+}
+
+#[derive(Default)]
+struct StderrLog {
+    lines: VecDeque<String>,
+    dropped: usize,
+}
+
+/// Spawns `command` with piped stdio, returning the transport and a guard
+/// that captures the child's stderr and owns its process group.
+///
+/// The child is deliberately not killed when the transport is dropped, so
+/// [`ServerProcess::shutdown`] can give it its grace period before signalling.
+pub fn spawn(command: &[String]) -> Result<(TokioChildProcess, ServerProcess)> {
+    let (program, args) = command.split_first().context("no server command given")?;
+    let mut cmd = tokio::process::Command::new(program);
+    cmd.args(args)
+        // Lets the Rust e2e server pick its stdio mode; other servers ignore it
+        .env("MCP_SERVER_TRANSPORT", "stdio");
+    #[cfg(unix)]
+    cmd.process_group(0);
+
+    let (transport, stderr) = TokioChildProcess::builder(cmd)
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("spawning server command {command:?}"))?;
+    let pid = transport.id();
+    tracing::info!("Spawned server process {pid:?}: {}", command.join(" "));
+
+    let log = Arc::new(Mutex::new(StderrLog::default()));
+    if let Some(stderr) = stderr {
+        let log = log.clone();
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(stderr).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                tracing::debug!(target: "server_stderr", "{line}");
+                let mut log = log.lock().unwrap();
+                if log.lines.len() == MAX_STDERR_LINES {
+                    log.lines.pop_front();
+                    log.dropped += 1;
+                }
+                log.lines.push_back(line);
+            }
+        });
+    }
+
+    Ok((transport, ServerProcess { pid, stderr: log }))
+}
+
+impl ServerProcess {
+    /// Everything the server wrote to stderr so far, oldest first.
+    pub fn stderr(&self) -> Vec<String> {
+        let log = self.stderr.lock().unwrap();
+        let mut lines = Vec::with_capacity(log.lines.len() + 1);
+        if log.dropped > 0 {
+            lines.push(format!("... {} earlier lines dropped", log.dropped));
+        }
+        lines.extend(log.lines.iter().cloned());
+        lines
+    }
+
+    /// Waits up to `grace` for the process group to exit on its own (the
+    /// server should stop once its stdin closes), then terminates it.
+    pub async fn shutdown(&mut self, grace: Duration) {
+        let Some(pid) = self.pid else {
+            return;
+        };
+        let deadline = tokio::time::Instant::now() + grace;
+        while group_alive(pid) && tokio::time::Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        if group_alive(pid) {
+            tracing::warn!("Server process group {pid} still running after {grace:?}, terminating");
+            signal_group(pid, Signal::Terminate);
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        }
+        self.kill();
+    }
+
+    fn kill(&mut self) {
+        if let Some(pid) = self.pid.take() {
+            signal_group(pid, Signal::Kill);
+        }
+    }
+}
+
+impl Drop for ServerProcess {
+    fn drop(&mut self) {
+        self.kill();
+    }
+}
+
+enum Signal {
+    Terminate,
+    Kill,
+}
+
+#[cfg(unix)]
+fn signal_group(pgid: u32, signal: Signal) {
+    let signal = match signal {
+        Signal::Terminate => libc::SIGTERM,
+        Signal::Kill => libc::SIGKILL,
+    };
+    // SAFETY: kill(2) has no memory-safety preconditions; a negative pid addresses the group.
+    unsafe {
+        libc::kill(-(pgid as libc::pid_t), signal);
+    }
+}
+
+#[cfg(unix)]
+fn group_alive(pgid: u32) -> bool {
+    // SAFETY: signal 0 only checks for existence.
+    unsafe { libc::kill(-(pgid as libc::pid_t), 0) == 0 }
+}
+
+// Without process groups only the direct child is cleaned up, by rmcp's
+// transport once it closes.
+#[cfg(not(unix))]
+fn signal_group(_pgid: u32, _signal: Signal) {}
+
+#[cfg(not(unix))]
+fn group_alive(_pgid: u32) -> bool {
+    false
+}
@@ -0,0 +1,395 @@
+//! Machine-readable run reports: a JSON document and JUnit XML.
+//!
+//! The JSON layout is versioned by [`SCHEMA_VERSION`]; bump it whenever a field
+//! is renamed or removed so the matrix aggregator can tell layouts apart.
+
+use std::{fmt::Write as _, path::Path, time::Duration};
+
+use anyhow::{Context as _, Result};
+use rmcp::model::{Implementation, ServerInfo};
+use serde::Serialize;
+
+use crate::{
+    cli::{ConnectArgs, TransportKind},
+    runner::{ScenarioResult, Status},
+};
+
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub schema_version: u32,
+    pub client: Implementation,
+    /// Endpoint for HTTP transports; absent for stdio.
+    pub server_url: Option<String>,
+    /// Spawned server command for the stdio transport; absent otherwise.
+    pub server_command: Option<Vec<String>>,
+    /// The `--transport` asked for, which may be `auto`.
+    pub requested_transport: &'static str,
+    /// The transport the session ran over; absent when the connection failed.
+    pub transport: Option<&'static str>,
+    /// What the server reported in `initialize`; absent when the connection failed.
+    pub server: Option<ServerDescription>,
+    pub duration_ms: u64,
+    pub summary: Summary,
+    pub scenarios: Vec<ScenarioReport>,
+    /// Every protocol version requested by the version scenarios and how the server answered.
+    pub protocol_negotiation: Vec<Negotiation>,
+    /// What a spawned server wrote to stderr; absent when no process was spawned.
+    pub server_stderr: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ServerDescription {
+    pub name: String,
+    pub version: String,
+    pub protocol_version: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct Summary {
+    pub total: usize,
+    pub passed: usize,
+    pub failed: usize,
+    pub skipped: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScenarioReport {
+    pub name: String,
+    pub description: String,
+    pub status: Status,
+    pub duration_ms: u64,
+    /// Failure message or skip reason; absent for passing scenarios.
+    pub error: Option<String>,
+}
+
+/// How the server answered one `initialize` requesting a given version.
+#[derive(Debug, Clone, Serialize)]
+pub struct Negotiation {
+    pub requested: String,
+    pub outcome: NegotiationOutcome,
+    /// The version in the server's `initialize` result; absent on error.
+    pub agreed: Option<String>,
+    /// Why the session could not be initialized; absent otherwise.
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NegotiationOutcome {
+    /// The server answered with the requested version.
+    Agreed,
+    /// The server answered with a different version.
+    CounterOffer,
+    /// `initialize` failed.
+    Error,
+}
+
+impl Negotiation {
+    pub fn answered(requested: &str, agreed: String) -> Self {
+        Self {
+            requested: requested.to_string(),
+            outcome: if agreed == requested {
+                NegotiationOutcome::Agreed
+            } else {
+                NegotiationOutcome::CounterOffer
+            },
+            agreed: Some(agreed),
+            error: None,
+        }
+    }
+
+    pub fn failed(requested: &str, error: String) -> Self {
+        Self {
+            requested: requested.to_string(),
+            outcome: NegotiationOutcome::Error,
+            agreed: None,
+            error: Some(error),
+        }
+    }
+
+    /// One-line form used in logs and the JUnit properties.
+    pub fn describe(&self) -> String {
+        match (self.outcome, &self.agreed, &self.error) {
+            (NegotiationOutcome::Agreed, _, _) => "agreed".to_string(),
+            (NegotiationOutcome::CounterOffer, Some(agreed), _) => format!("counter-offered {agreed}"),
+            (_, _, error) => format!("error: {}", error.as_deref().unwrap_or_default()),
+        }
+    }
+}
+
+impl Report {
+    pub fn new(
+        client: Implementation,
+        target: &ConnectArgs,
+        transport: Option<TransportKind>,
+        server_info: Option<&ServerInfo>,
+        duration: Duration,
+        results: &[ScenarioResult],
+    ) -> Self {
+        let mut summary = Summary {
+            total: results.len(),
+            ..Default::default()
+        };
+        for result in results {
+            match result.status {
+                Status::Passed => summary.passed += 1,
+                Status::Failed => summary.failed += 1,
+                Status::Skipped => summary.skipped += 1,
+            }
+        }
+        Self {
+            schema_version: SCHEMA_VERSION,
+            client,
+            server_url: (target.transport != TransportKind::Stdio).then(|| target.server_url.clone()),
+            server_command: (target.transport == TransportKind::Stdio).then(|| target.server_command.clone()),
+            requested_transport: target.transport.name(),
+            transport: transport.map(TransportKind::name),
+            server: server_info.map(|info| ServerDescription {
+                name: info.server_info.name.clone(),
+                version: info.server_info.version.clone(),
+                protocol_version: protocol_version(info),
+            }),
+            duration_ms: duration.as_millis() as u64,
+            summary,
+            scenarios: results
+                .iter()
+                .map(|r| ScenarioReport {
+                    name: r.name.to_string(),
+                    description: r.description.to_string(),
+                    status: r.status,
+                    duration_ms: r.duration.as_millis() as u64,
+                    error: r.detail.clone(),
+                })
+                .collect(),
+            protocol_negotiation: Vec::new(),
+            server_stderr: None,
+        }
+    }
+
+    pub fn write_json(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, json + "\n").with_context(|| format!("writing JSON report to {}", path.display()))
+    }
+
+    pub fn write_junit(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, self.to_junit()).with_context(|| format!("writing JUnit report to {}", path.display()))
+    }
+
+    fn to_junit(&self) -> String {
+        let target = match (&self.server_url, &self.server_command) {
+            (Some(url), _) => url.clone(),
+            (None, Some(command)) => command.join(" "),
+            (None, None) => String::new(),
+        };
+        let suite = match &self.server {
+            Some(server) => format!("{} -> {} {}", self.client.name, server.name, server.version),
+            None => format!("{} -> {}", self.client.name, target),
+        };
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        let _ = writeln!(
+            xml,
+            "<testsuites name=\"mcpbench\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{}\">",
+            self.summary.total,
+            self.summary.failed,
+            self.summary.skipped,
+            seconds(self.duration_ms)
+        );
+        let _ = writeln!(
+            xml,
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"0\" skipped=\"{}\" time=\"{}\">",
+            escape(&suite),
+            self.summary.total,
+            self.summary.failed,
+            self.summary.skipped,
+            seconds(self.duration_ms)
+        );
+        let _ = writeln!(xml, "    <properties>");
+        let _ = writeln!(xml, "      <property name=\"server\" value=\"{}\"/>", escape(&target));
+        let transport = self.transport.unwrap_or(self.requested_transport);
+        let _ = writeln!(xml, "      <property name=\"transport\" value=\"{transport}\"/>");
+        if let Some(server) = &self.server {
+            let _ = writeln!(
+                xml,
+                "      <property name=\"protocol_version\" value=\"{}\"/>",
+                escape(&server.protocol_version)
+            );
+        }
+        for negotiation in &self.protocol_negotiation {
+            let _ = writeln!(
+                xml,
+                "      <property name=\"protocol_negotiation.{}\" value=\"{}\"/>",
+                escape(&negotiation.requested),
+                escape(&negotiation.describe())
+            );
+        }
+        let _ = writeln!(xml, "    </properties>");
+        for scenario in &self.scenarios {
+            let _ = write!(
+                xml,
+                "    <testcase name=\"{}\" classname=\"{}\" time=\"{}\"",
+                escape(&scenario.name),
+                escape(&suite),
+                seconds(scenario.duration_ms)
+            );
+            let detail = escape(scenario.error.as_deref().unwrap_or_default());
+            match scenario.status {
+                Status::Passed => xml.push_str("/>\n"),
+                Status::Failed => {
+                    let _ = writeln!(xml, ">\n      <failure message=\"{detail}\">{detail}</failure>\n    </testcase>");
+                }
+                Status::Skipped => {
+                    let _ = writeln!(xml, ">\n      <skipped message=\"{detail}\"/>\n    </testcase>");
+                }
+            }
+        }
+        if let Some(stderr) = &self.server_stderr {
+            let _ = writeln!(xml, "    <system-err>{}</system-err>", escape_text(&stderr.join("\n")));
+        }
+        xml.push_str("  </testsuite>\n</testsuites>\n");
+        xml
+    }
+}
+
+/// The negotiated version exactly as it appears on the wire.
+pub fn protocol_version(info: &ServerInfo) -> String {
+    match serde_json::to_value(&info.protocol_version) {
+        Ok(serde_json::Value::String(version)) => version,
+        other => format!("{other:?}"),
+    }
+}
+
+fn seconds(millis: u64) -> String {
+    format!("{:.3}", millis as f64 / 1000.0)
+}
+
+/// Escapes element text, keeping newlines readable.
+fn escape_text(text: &str) -> String {
+    text.split('\n').map(escape).collect::<Vec<_>>().join("\n")
+}
+
+/// Escapes attribute values and single-line text.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\n' => escaped.push_str("&#10;"),
+            // Other control characters are not allowed in XML 1.0
+            c if c.is_control() && c != '\t' && c != '\r' => escaped.push('\u{FFFD}'),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(results: &[ScenarioResult]) -> Report {
+        let target = ConnectArgs {
+            server_url: "http://localhost:8000/mcp?a=1&b=2".to_string(),
+            server_command: Vec::new(),
+            transport: TransportKind::StreamableHttp,
+            protocol_version: None,
+            request_timeout: 30,
+        };
+        let client = Implementation {
+            name: "test client".to_string(),
+            title: None,
+            version: "0.0.0".to_string(),
+            icons: None,
+            website_url: None,
+        };
+        Report::new(client, &target, Some(TransportKind::StreamableHttp), None, Duration::from_millis(1500), results)
+    }
+
+    fn failed(detail: &str) -> ScenarioResult {
+        ScenarioResult {
+            name: "tools_call",
+            description: "Calls a tool",
+            status: Status::Failed,
+            duration: Duration::from_millis(250),
+            detail: Some(detail.to_string()),
+        }
+    }
+
+    #[test]
+    fn escape_handles_markup_quotes_and_newlines() {
+        assert_eq!(escape(r#"<a href="x">'&'</a>"#), "&lt;a href=&quot;x&quot;&gt;&apos;&amp;&apos;&lt;/a&gt;");
+        assert_eq!(escape("line one\nline two"), "line one&#10;line two");
+        assert_eq!(escape("tab\tand\rreturn"), "tab\tand\rreturn");
+    }
+
+    #[test]
+    fn escape_replaces_control_characters_xml_forbids() {
+        assert_eq!(escape("nul\0 bell\u{7} esc\u{1b}"), "nul\u{FFFD} bell\u{FFFD} esc\u{FFFD}");
+    }
+
+    #[test]
+    fn escape_text_keeps_newlines_literal() {
+        assert_eq!(escape_text("a < b\n\"c\""), "a &lt; b\n&quot;c&quot;");
+    }
+
+    #[test]
+    fn junit_failure_message_is_escaped() {
+        let xml = report(&[failed("expected <text> but got \"image\"\nat content[0]\u{0}")]).to_junit();
+        let message = "expected &lt;text&gt; but got &quot;image&quot;&#10;at content[0]\u{FFFD}";
+        assert!(xml.contains(&format!("<failure message=\"{message}\">{message}</failure>")), "{xml}");
+        assert!(xml.contains("value=\"http://localhost:8000/mcp?a=1&amp;b=2\""), "{xml}");
+        assert!(xml.contains("tests=\"1\" failures=\"1\""), "{xml}");
+        assert!(!xml.contains('\0'));
+    }
+
+    /// Renaming or removing any of these fields changes the JSON layout; bump
+    /// [`SCHEMA_VERSION`] and update this snapshot together.
+    #[test]
+    fn json_field_names_match_schema_version() {
+        assert_eq!(SCHEMA_VERSION, 1);
+
+        let mut report = report(&[failed("boom")]);
+        report.server = Some(ServerDescription {
+            name: "server".to_string(),
+            version: "1.0.0".to_string(),
+            protocol_version: "2025-06-18".to_string(),
+        });
+        report.protocol_negotiation = vec![Negotiation::answered("2025-03-26", "2025-03-26".to_string())];
+        report.server_stderr = Some(vec!["ready".to_string()]);
+        let json = serde_json::to_value(&report).unwrap();
+
+        let keys = |value: &serde_json::Value| -> Vec<String> {
+            let mut keys: Vec<_> = value.as_object().unwrap().keys().cloned().collect();
+            keys.sort();
+            keys
+        };
+        assert_eq!(
+            keys(&json),
+            [
+                "client",
+                "duration_ms",
+                "protocol_negotiation",
+                "requested_transport",
+                "scenarios",
+                "schema_version",
+                "server",
+                "server_command",
+                "server_stderr",
+                "server_url",
+                "summary",
+                "transport",
+            ]
+        );
+        assert_eq!(keys(&json["server"]), ["name", "protocol_version", "version"]);
+        assert_eq!(keys(&json["summary"]), ["failed", "passed", "skipped", "total"]);
+        assert_eq!(keys(&json["scenarios"][0]), ["description", "duration_ms", "error", "name", "status"]);
+        assert_eq!(keys(&json["protocol_negotiation"][0]), ["agreed", "error", "outcome", "requested"]);
+        assert_eq!(json["scenarios"][0]["status"], "failed");
+        assert_eq!(json["protocol_negotiation"][0]["outcome"], "agreed");
+    }
+}
@@ -0,0 +1,232 @@
+//! Runs scenarios against a connected server and records a status for each.
+
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use rmcp::{
+    Peer, RoleClient,
+    model::{ServerInfo, Tool},
+};
+use serde::Serialize;
+
+use crate::{
+    cli::{ConnectArgs, TransportKind},
+    handler::E2eClient,
+    report::{self, Negotiation},
+};
+
+/// Everything a scenario needs: the peer, our handler's state and what the server advertised.
+#[derive(Clone)]
+pub struct Context {
+    pub client: Peer<RoleClient>,
+    pub handler: E2eClient,
+    pub server_info: Option<ServerInfo>,
+    /// Every tool the server listed, across all pages.
+    pub tools: Arc<Vec<Tool>>,
+    /// How many `tools/list` pages it took to collect [`Context::tools`].
+    pub tool_pages: usize,
+    /// Deadline for a single request made through [`Context::request`].
+    pub request_timeout: Duration,
+    /// The transport the session runs over; never [`TransportKind::Auto`].
+    pub transport: TransportKind,
+    /// How many generated tools `--expect-generated-tools` says the server has.
+    pub expected_generated_tools: Option<usize>,
+    /// What `--expect-transport` asked the session to end up on.
+    pub expected_transport: Option<TransportKind>,
+    /// How the main session connected, with the negotiated transport, for
+    /// scenarios that open a [`Session`](crate::session::Session) of their own.
+    pub connect: Arc<ConnectArgs>,
+    /// Version negotiations recorded by scenarios, copied into the report.
+    pub negotiations: Arc<std::sync::Mutex<Vec<Negotiation>>>,
+}
+
+impl Context {
+    pub fn tool(&self, name: &str) -> Option<&Tool> {
+        self.tools.iter().find(|t| t.name == name)
+    }
+
+    pub fn has_tool(&self, name: &str) -> bool {
+        self.tool(name).is_some()
+    }
+
+    /// The version the main session agreed on, as sent on the wire.
+    pub fn protocol_version(&self) -> Option<String> {
+        self.server_info.as_ref().map(report::protocol_version)
+    }
+
+    pub fn record_negotiation(&self, negotiation: Negotiation) {
+        tracing::info!("Requested {:?}: {}", negotiation.requested, negotiation.describe());
+        self.negotiations.lock().unwrap().push(negotiation);
+    }
+
+    /// Awaits one request, failing it once the per-request timeout elapses.
+    pub async fn request<T, E>(&self, request: impl Future<Output = Result<T, E>>) -> Result<T>
+    where
+        E: Into<anyhow::Error>,
+    {
+        match tokio::time::timeout(self.request_timeout, request).await {
+            Ok(result) => result.map_err(Into::into),
+            Err(_) => anyhow::bail!("no response within {:?}", self.request_timeout),
+        }
+    }
+}
+
+/// How a scenario that did not fail ended.
+#[derive(Debug)]
+pub enum Outcome {
+    Passed,
+    /// The server lacks what the scenario needs; the reason is reported.
+    Skipped(String),
+}
+
+impl Outcome {
+    pub fn skipped(reason: impl Into<String>) -> Self {
+        Outcome::Skipped(reason.into())
+    }
+
+    pub fn missing_tools(names: &[&str]) -> Self {
+        Outcome::Skipped(format!("server does not expose {}", names.join(", ")))
+    }
+}
+
+pub type ScenarioFuture = Pin<Box<dyn Future<Output = Result<Outcome>> + Send>>;
+pub type ScenarioFn = fn(Context) -> ScenarioFuture;
+
+pub struct Scenario {
+    /// Stable identifier used in reports.
+    pub name: &'static str,
+    pub description: &'static str,
+    run: ScenarioFn,
+}
+
+impl Scenario {
+    pub fn new(name: &'static str, description: &'static str, run: ScenarioFn) -> Self {
+        Self { name, description, run }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+#[derive(Debug, Clone)]
+pub struct ScenarioResult {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub status: Status,
+    pub duration: Duration,
+    /// Failure message or skip reason.
+    pub detail: Option<String>,
+}
+
+/// Which scenarios to run, by name. Patterns may use `*` as a wildcard.
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    /// Run only scenarios matching one of these; empty means all.
+    pub include: Vec<String>,
+    /// Never run scenarios matching one of these, even if included.
+    pub exclude: Vec<String>,
+}
+
+impl Filter {
+    pub fn matches(&self, name: &str) -> bool {
+        (self.include.is_empty() || self.include.iter().any(|p| glob_match(p, name)))
+            && !self.exclude.iter().any(|p| glob_match(p, name))
+    }
+}
+
+fn glob_match(pattern: &str, name: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == name,
+        Some((prefix, rest)) => {
+            let Some(mut remaining) = name.strip_prefix(prefix) else {
+                return false;
+            };
+            // Try every split point for the text the `*` absorbs
+            loop {
+                if glob_match(rest, remaining) {
+                    return true;
+                }
+                let mut chars = remaining.chars();
+                if chars.next().is_none() {
+                    return false;
+                }
+                remaining = chars.as_str();
+            }
+        }
+    }
+}
+
+/// Runs `scenarios` in order, each bounded by `timeout`. A failing, panicking or
+/// timed-out scenario is recorded and the run continues with the next one.
+pub async fn run(scenarios: &[&Scenario], ctx: &Context, timeout: Duration) -> Vec<ScenarioResult> {
+    let mut results = Vec::with_capacity(scenarios.len());
+    for (index, scenario) in scenarios.iter().enumerate() {
+        tracing::info!("\n{}. {}...", index + 1, scenario.description);
+
+        let started = Instant::now();
+        let task = tokio::spawn((scenario.run)(ctx.clone()));
+        let abort = task.abort_handle();
+        let outcome = match tokio::time::timeout(timeout, task).await {
+            Ok(Ok(outcome)) => outcome,
+            Ok(Err(e)) => Err(anyhow::anyhow!("scenario panicked: {e}")),
+            Err(_) => {
+                abort.abort();
+                Err(anyhow::anyhow!("scenario did not finish within {timeout:?}"))
+            }
+        };
+        let duration = started.elapsed();
+
+        let (status, detail) = match outcome {
+            Ok(Outcome::Passed) => (Status::Passed, None),
+            Ok(Outcome::Skipped(reason)) => (Status::Skipped, Some(reason)),
+            Err(error) => (Status::Failed, Some(format!("{error:#}"))),
+        };
+        match (status, &detail) {
+            (Status::Passed, _) => tracing::info!("✓ {} passed in {duration:.2?}", scenario.name),
+            (Status::Skipped, Some(reason)) => tracing::info!("- {} skipped: {reason}", scenario.name),
+            (_, detail) => tracing::error!("✗ {} failed: {}", scenario.name, detail.as_deref().unwrap_or_default()),
+        }
+        results.push(ScenarioResult {
+            name: scenario.name,
+            description: scenario.description,
+            status,
+            duration,
+            detail,
+        });
+    }
+    results
+}
+
+/// Logs one line per scenario and returns true when none failed.
+pub fn summarize(results: &[ScenarioResult]) -> bool {
+    let count = |status| results.iter().filter(|r| r.status == status).count();
+    tracing::info!("\n=== Summary ===");
+    for result in results {
+        let mark = match result.status {
+            Status::Passed => "✓",
+            Status::Failed => "✗",
+            Status::Skipped => "-",
+        };
+        match &result.detail {
+            Some(detail) => tracing::info!("{mark} {:<22} {detail}", result.name),
+            None => tracing::info!("{mark} {}", result.name),
+        }
+    }
+    tracing::info!(
+        "{} passed, {} failed, {} skipped",
+        count(Status::Passed),
+        count(Status::Failed),
+        count(Status::Skipped)
+    );
+    count(Status::Failed) == 0
+}
@@ -0,0 +1,949 @@
+//! The e2e scenarios, in the order they run.
+//!
+//! Each scenario skips itself when the server lacks the tool or capability it
+//! exercises, so the same client can run against every server in the matrix.
+
+use anyhow::{Result, bail, ensure};
+use mcpbench_contract::{
+    Contract, check,
+    logging::{LOG_LEVELS, severity},
+    spec::{ResourceSource, ToolCase, ToolExpectation},
+};
+use rmcp::{
+    Peer, RoleClient, ServiceError,
+    model::{
+        CallToolRequest, CallToolRequestParam, CallToolResult, CancelledNotificationParam, ClientRequest,
+        GetPromptRequestParam, LoggingLevel, PaginatedRequestParam, ReadResourceRequestParam, ServerResult,
+        SetLevelRequestParam, Tool,
+    },
+    service::PeerRequestOptions,
+};
+use std::{future::Future, time::Duration};
+
+use crate::{
+    handler::{self, ElicitationReply},
+    report::{Negotiation, NegotiationOutcome},
+    runner::{Context, Outcome, Scenario},
+    session::Session,
+};
+
+pub fn all() -> Vec<Scenario> {
+    vec![
+        Scenario::new("transport_negotiation", "Checking the negotiated transport", |ctx| {
+            Box::pin(transport_negotiation(ctx))
+        }),
+        Scenario::new("protocol_versions", "Negotiating each protocol version", |ctx| {
+            Box::pin(protocol_versions(ctx))
+        }),
+        Scenario::new("protocol_mismatch", "Requesting versions the server cannot agree to", |ctx| {
+            Box::pin(protocol_mismatch(ctx))
+        }),
+        Scenario::new("tools_list", "Listing available tools", |ctx| Box::pin(tools_list(ctx))),
+        Scenario::new("send_message", "Testing send_message tool", |ctx| Box::pin(send_message(ctx))),
+        Scenario::new("server_info", "Testing get_server_info tool", |ctx| Box::pin(server_info(ctx))),
+        Scenario::new("increment", "Testing increment tool", |ctx| Box::pin(increment(ctx))),
+        Scenario::new("resources", "Testing resources", |ctx| Box::pin(resources(ctx))),
+        Scenario::new("prompts", "Testing prompts", |ctx| Box::pin(prompts(ctx))),
+        Scenario::new(
+            "sampling",
+            "Testing sample_llm tool (server -> client sampling)",
+            |ctx| Box::pin(sampling(ctx)),
+        ),
+        Scenario::new(
+            "roots",
+            "Testing list_roots tool (server -> client roots/list)",
+            |ctx| Box::pin(roots(ctx)),
+        ),
+        Scenario::new(
+            "elicitation",
+            "Testing elicit_user_info tool (server -> client elicitation)",
+            |ctx| Box::pin(elicitation(ctx)),
+        ),
+        Scenario::new(
+            "progress",
+            "Testing long_running_operation tool (progress notifications)",
+            |ctx| Box::pin(progress(ctx)),
+        ),
+        Scenario::new(
+            "cancellation",
+            "Testing slow_operation cancellation (notifications/cancelled)",
+            |ctx| Box::pin(cancellation(ctx)),
+        ),
+        Scenario::new("logging", "Testing logging/setLevel and notifications/message", |ctx| {
+            Box::pin(logging(ctx))
+        }),
+        Scenario::new("dynamic_tools", "Testing add_tool/remove_tool (tools/list_changed)", |ctx| {
+            Box::pin(dynamic_tools(ctx))
+        }),
+        Scenario::new("structured_output", "Testing structured tool output (outputSchema)", |ctx| {
+            Box::pin(structured_output(ctx))
+        }),
+        Scenario::new(
+            "content_types",
+            "Testing content types (image, audio, embedded resource, resource link)",
+            |ctx| Box::pin(content_types(ctx)),
+        ),
+        Scenario::new("argument_validation", "Testing argument validation (invalid_params)", |ctx| {
+            Box::pin(argument_validation(ctx))
+        }),
+        Scenario::new("contract", "Checking the fixture contract", |ctx| Box::pin(contract(ctx))),
+    ]
+}
+
+async fn transport_negotiation(ctx: Context) -> Result<Outcome> {
+    tracing::info!("Session runs over {}", ctx.transport.name());
+    let Some(expected) = ctx.expected_transport else {
+        return Ok(Outcome::skipped("no --expect-transport given"));
+    };
+    ensure!(
+        ctx.transport == expected,
+        "expected the session to run over {}, but it negotiated {}",
+        expected.name(),
+        ctx.transport.name()
+    );
+    tracing::info!("✓ Negotiated the expected transport");
+    Ok(Outcome::Passed)
+}
+
+/// Protocol revisions requested in turn by `protocol_versions`, oldest first.
+const PROTOCOL_VERSIONS: [&str; 3] = ["2024-11-05", "2025-03-26", "2025-06-18"];
+
+/// First revision with tool output schemas and structured content.
+const STRUCTURED_OUTPUT_SINCE: &str = "2025-06-18";
+
+/// Tool fields and the revision that introduced each.
+const TOOL_FIELD_GATES: [(&str, &str); 2] = [("annotations", "2025-03-26"), ("outputSchema", "2025-06-18")];
+
+/// Opens a session per revision in [`PROTOCOL_VERSIONS`] and checks that the
+/// server agrees to it or counter-offers another known revision, and that tool
+/// fields only appear under revisions that define them.
+async fn protocol_versions(ctx: Context) -> Result<Outcome> {
+    let mut sessions = Vec::new();
+    for requested in PROTOCOL_VERSIONS {
+        let session = Session::open(&ctx, requested).await?;
+        let negotiated = negotiated_tools(&ctx, &session).await;
+        session.close().await;
+        let (agreed, tools) = negotiated?;
+        ctx.record_negotiation(Negotiation::answered(requested, agreed.clone()));
+        ensure!(
+            PROTOCOL_VERSIONS.contains(&agreed.as_str()),
+            "requested {requested}, but the server answered with unknown version {agreed:?}"
+        );
+        if agreed == requested {
+            tracing::info!("✓ Agreed on {agreed}");
+        } else {
+            tracing::info!("Requested {requested}, server counter-offered {agreed}");
+        }
+        sessions.push((requested, agreed, tools));
+    }
+    ensure!(
+        sessions.iter().any(|(requested, agreed, _)| agreed == requested),
+        "server agreed to none of {PROTOCOL_VERSIONS:?}"
+    );
+
+    for (field, since) in TOOL_FIELD_GATES {
+        // A field no session ever carries is simply unused by this server
+        let users: std::collections::BTreeSet<_> = sessions
+            .iter()
+            .flat_map(|(.., tools)| tools.iter().filter(|t| has_field(t, field)).map(|t| t.name.clone()))
+            .collect();
+        if users.is_empty() {
+            continue;
+        }
+        for (_, agreed, tools) in &sessions {
+            let present: std::collections::BTreeSet<_> =
+                tools.iter().filter(|t| has_field(t, field)).map(|t| t.name.clone()).collect();
+            if agreed.as_str() >= since {
+                let missing: Vec<_> = users.difference(&present).collect();
+                ensure!(missing.is_empty(), "{field} missing from {missing:?} under {agreed}");
+            } else {
+                ensure!(present.is_empty(), "{field} on {present:?} under {agreed}, which predates {since}");
+            }
+        }
+        tracing::info!("✓ Tool {field} only under {since} and later");
+    }
+    Ok(Outcome::Passed)
+}
+
+/// Versions no server should agree to, and whether rejecting them outright is
+/// acceptable. The spec requires a counter-offer for a well-formed version the
+/// server does not support; a string that is not a version may also be refused.
+const MISMATCHED_VERSIONS: [(&str, &str, bool); 3] = [
+    ("future", "2099-01-01", false),
+    ("older", "2024-10-07", false),
+    ("malformed", "not-a-version", true),
+];
+
+async fn protocol_mismatch(ctx: Context) -> Result<Outcome> {
+    let mut failures = Vec::new();
+    for (case, requested, may_reject) in MISMATCHED_VERSIONS {
+        let negotiation = match Session::open(&ctx, requested).await {
+            Ok(session) => {
+                let agreed = session.protocol_version();
+                session.close().await;
+                match agreed {
+                    Some(agreed) => Negotiation::answered(requested, agreed),
+                    None => Negotiation::failed(requested, "no initialize result".to_string()),
+                }
+            }
+            Err(e) => Negotiation::failed(requested, format!("{e:#}")),
+        };
+        ctx.record_negotiation(negotiation.clone());
+
+        let agreed = negotiation.agreed.as_deref().unwrap_or_default();
+        match negotiation.outcome {
+            NegotiationOutcome::Agreed => failures.push(format!("server agreed to {case} version {requested:?}")),
+            NegotiationOutcome::CounterOffer if !PROTOCOL_VERSIONS.contains(&agreed) => failures.push(format!(
+                "server counter-offered unknown version {agreed:?} to {case} version {requested:?}"
+            )),
+            NegotiationOutcome::CounterOffer => tracing::info!("✓ {case}: counter-offered {agreed}"),
+            NegotiationOutcome::Error if may_reject => tracing::info!("✓ {case}: rejected"),
+            NegotiationOutcome::Error => failures.push(format!(
+                "server rejected {case} version {requested:?} instead of counter-offering: {}",
+                negotiation.error.as_deref().unwrap_or_default()
+            )),
+        }
+    }
+    ensure!(failures.is_empty(), "{}", failures.join("; "));
+    Ok(Outcome::Passed)
+}
+
+/// The version `session` agreed on and the tools it lists, if the server has any.
+async fn negotiated_tools(ctx: &Context, session: &Session) -> Result<(String, Vec<Tool>)> {
+    let agreed = session
+        .protocol_version()
+        .ok_or_else(|| anyhow::anyhow!("server did not return initialize information"))?;
+    let has_tools = session.client.peer_info().is_some_and(|info| info.capabilities.tools.is_some());
+    let tools = if has_tools {
+        ctx.request(list_all_tool_pages(&session.client)).await?.0
+    } else {
+        Vec::new()
+    };
+    Ok((agreed, tools))
+}
+
+fn has_field(tool: &Tool, field: &str) -> bool {
+    serde_json::to_value(tool).is_ok_and(|tool| tool.get(field).is_some_and(|value| !value.is_null()))
+}
+
+async fn tools_list(ctx: Context) -> Result<Outcome> {
+    tracing::info!("Available tools ({} across {} page(s)):", ctx.tools.len(), ctx.tool_pages);
+    for tool in ctx.tools.iter().filter(|t| !t.name.starts_with("generated_tool_")) {
+        tracing::info!("  - {}: {}", tool.name, tool.description.as_deref().unwrap_or("No description"));
+        tracing::info!("    Schema: {}", serde_json::to_string_pretty(&tool.input_schema).unwrap());
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    let duplicates: Vec<_> = ctx.tools.iter().filter(|t| !seen.insert(&t.name)).map(|t| &t.name).collect();
+    ensure!(duplicates.is_empty(), "tools returned more than once across pages: {duplicates:?}");
+
+    let generated: std::collections::BTreeSet<usize> = ctx
+        .tools
+        .iter()
+        .filter_map(|t| t.name.strip_prefix("generated_tool_")?.parse().ok())
+        .collect();
+    match ctx.expected_generated_tools {
+        Some(expected) => {
+            // Comparing against the configured total also catches dropped trailing pages
+            let missing: Vec<_> = (0..expected).filter(|i| !generated.contains(i)).collect();
+            ensure!(
+                missing.is_empty() && generated.len() == expected,
+                "expected generated tools 0..{expected}, got {} (missing {missing:?})",
+                generated.len()
+            );
+            tracing::info!("✓ All {expected} generated tools returned exactly once");
+        }
+        None if !generated.is_empty() => {
+            ensure!(
+                generated.iter().copied().eq(0..generated.len()),
+                "generated tools are missing from the paginated listing: got {} of {}",
+                generated.len(),
+                generated.last().unwrap() + 1
+            );
+            tracing::info!(
+                "✓ {} generated tools without gaps; pass --expect-generated-tools to check the total",
+                generated.len()
+            );
+        }
+        None => {}
+    }
+
+    if ctx.tool_pages > 1 {
+        let invalid = ctx
+            .request(ctx.client.list_tools(Some(PaginatedRequestParam {
+                cursor: Some("not-a-valid-cursor".to_string()),
+            })))
+            .await;
+        ensure!(invalid.is_err(), "server accepted an invalid cursor: {invalid:?}");
+        tracing::info!("✓ Invalid cursor rejected");
+    }
+    Ok(Outcome::Passed)
+}
+
+async fn send_message(ctx: Context) -> Result<Outcome> {
+    if !ctx.has_tool("send_message") {
+        return Ok(Outcome::missing_tools(&["send_message"]));
+    }
+
+    let message = "Hello from Rust E2E client!";
+    let tool_result = call(&ctx, "send_message", serde_json::json!({ "message": message })).await?;
+    let text = text_of(&tool_result)?;
+    ensure!(text == format!("Received message: {message}"), "unexpected send_message reply: {text:?}");
+    tracing::info!("✓ {text}");
+    Ok(Outcome::Passed)
+}
+
+async fn server_info(ctx: Context) -> Result<Outcome> {
+    if !ctx.has_tool("get_server_info") {
+        return Ok(Outcome::missing_tools(&["get_server_info"]));
+    }
+
+    let tool_result = call(&ctx, "get_server_info", serde_json::json!({})).await?;
+    let text = text_of(&tool_result)?;
+    let json = text
+        .strip_prefix("Server Information: ")
+        .ok_or_else(|| anyhow::anyhow!("unexpected get_server_info reply: {text:?}"))?;
+    let info: serde_json::Value =
+        serde_json::from_str(json).map_err(|e| anyhow::anyhow!("server information is not JSON: {e}"))?;
+    tracing::info!("Server information: {info:#}");
+
+    if let Some(advertised) = &ctx.server_info {
+        ensure!(
+            info["name"] == advertised.server_info.name.as_str(),
+            "get_server_info reports name {}, but initialize reported {:?}",
+            info["name"],
+            advertised.server_info.name
+        );
+    }
+    Ok(Outcome::Passed)
+}
+
+async fn increment(ctx: Context) -> Result<Outcome> {
+    if !ctx.has_tool("increment") {
+        return Ok(Outcome::missing_tools(&["increment"]));
+    }
+
+    let first = counter_after(&ctx, 42).await?;
+    let second = counter_after(&ctx, 8).await?;
+    ensure!(second == first + 8, "counter went from {first} to {second} after incrementing by 8");
+    tracing::info!("✓ Counter accumulated within the session ({first} -> {second})");
+    Ok(Outcome::Passed)
+}
+
+async fn resources(ctx: Context) -> Result<Outcome> {
+    if ctx.server_info.as_ref().is_none_or(|info| info.capabilities.resources.is_none()) {
+        return Ok(Outcome::skipped("server does not advertise the resources capability"));
+    }
+
+    let resources = ctx.request(ctx.client.list_all_resources()).await?;
+    tracing::info!("Available resources:");
+    for resource in &resources {
+        tracing::info!("  - {} ({})", resource.uri, resource.mime_type.as_deref().unwrap_or("unknown"));
+    }
+
+    let templates = ctx.request(ctx.client.list_all_resource_templates()).await?;
+    tracing::info!("Available resource templates:");
+    for template in &templates {
+        tracing::info!("  - {}", template.uri_template);
+    }
+
+    for resource in mcpbench_contract::contract().resources {
+        let listed = match resource.source {
+            ResourceSource::Static => resources.iter().any(|r| r.uri == resource.uri),
+            ResourceSource::Template(template) => templates.iter().any(|t| t.uri_template == template),
+        };
+        if !listed {
+            continue;
+        }
+        let response = respond(&ctx, ctx.client.read_resource(ReadResourceRequestParam { uri: resource.uri.clone() }))
+            .await?;
+        check::check_read_response(response, &resource).map_err(|e| anyhow::anyhow!("{}: {e}", resource.uri))?;
+        tracing::info!("✓ {} matches the fixture", resource.uri);
+    }
+    Ok(Outcome::Passed)
+}
+
+async fn prompts(ctx: Context) -> Result<Outcome> {
+    if ctx.server_info.as_ref().is_none_or(|info| info.capabilities.prompts.is_none()) {
+        return Ok(Outcome::skipped("server does not advertise the prompts capability"));
+    }
+
+    let prompts = ctx.request(ctx.client.list_all_prompts()).await?;
+    tracing::info!("Available prompts:");
+    for prompt in &prompts {
+        let arguments: Vec<_> = prompt
+            .arguments
+            .iter()
+            .flatten()
+            .map(|arg| format!("{}{}", arg.name, if arg.required == Some(true) { "*" } else { "" }))
+            .collect();
+        tracing::info!("  - {}({})", prompt.name, arguments.join(", "));
+    }
+
+    for prompt in mcpbench_contract::contract().prompts {
+        if !prompts.iter().any(|p| p.name == prompt.name) {
+            continue;
+        }
+        for case in &prompt.cases {
+            let response = respond(
+                &ctx,
+                ctx.client.get_prompt(GetPromptRequestParam {
+                    name: prompt.name.to_string(),
+                    arguments: case.arguments.as_object().cloned(),
+                }),
+            )
+            .await?;
+            check::check_prompt_response(response, &case.expect)
+                .map_err(|e| anyhow::anyhow!("{} [{}]: {e}", prompt.name, case.label))?;
+            tracing::info!("✓ {} [{}]", prompt.name, case.label);
+        }
+    }
+    Ok(Outcome::Passed)
+}
+
+async fn sampling(ctx: Context) -> Result<Outcome> {
+    if !ctx.has_tool("sample_llm") {
+        return Ok(Outcome::missing_tools(&["sample_llm"]));
+    }
+
+    let prompt = "Ping from sampling test";
+    let tool_result = call(
+        &ctx,
+        "sample_llm",
+        serde_json::json!({
+            "prompt": prompt,
+            "max_tokens": 64
+        }),
+    )
+    .await?;
+
+    let text = text_of(&tool_result)?;
+    let expected = format!("LLM sampling result: {}", handler::fake_completion(prompt));
+    ensure!(text == expected, "sampling result did not round-trip: {text:?}");
+    tracing::info!("✓ Sampling result flowed back through call_tool");
+    Ok(Outcome::Passed)
+}
+
+async fn roots(ctx: Context) -> Result<Outcome> {
+    if !ctx.has_tool("list_roots") {
+        return Ok(Outcome::missing_tools(&["list_roots"]));
+    }
+
+    let expected = ctx.handler.roots();
+    let seen = call_list_roots(&ctx).await?;
+    tracing::info!("Server saw roots: {seen:#}");
+    ensure!(
+        root_uris(&seen) == expected.iter().map(|r| r.uri.as_str()).collect::<Vec<_>>(),
+        "server saw different roots than advertised: {seen}"
+    );
+    let notifications_before = seen["list_changed_notifications"].as_u64();
+
+    tracing::info!("Mutating roots and sending notifications/roots/list_changed...");
+    let mut updated = expected;
+    updated.pop();
+    updated.extend(handler::roots_from_uris(["file:///tmp/mcpbench/added"]));
+    ctx.handler.set_roots(updated.clone());
+    ctx.request(ctx.client.notify_roots_list_changed()).await?;
+
+    // The notification and the next tool call travel on separate requests,
+    // so give the server a few chances to observe the notification first.
+    let mut seen = call_list_roots(&ctx).await?;
+    for _ in 0..10 {
+        if seen["list_changed_notifications"].as_u64() != notifications_before {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        seen = call_list_roots(&ctx).await?;
+    }
+    tracing::info!("Server saw roots: {seen:#}");
+    ensure!(
+        root_uris(&seen) == updated.iter().map(|r| r.uri.as_str()).collect::<Vec<_>>(),
+        "server did not see updated roots: {seen}"
+    );
+    if let (Some(before), Some(after)) = (notifications_before, seen["list_changed_notifications"].as_u64()) {
+        ensure!(after == before + 1, "server observed {} list_changed notifications, expected 1", after - before);
+        tracing::info!("✓ roots/list_changed observed by server");
+    }
+    tracing::info!("✓ Server saw updated roots");
+    Ok(Outcome::Passed)
+}
+
+async fn elicitation(ctx: Context) -> Result<Outcome> {
+    if !ctx.has_tool("elicit_user_info") {
+        return Ok(Outcome::missing_tools(&["elicit_user_info"]));
+    }
+
+    let form = serde_json::json!({ "name": "MCPBench", "age": 42, "subscribe": true });
+    let cases = [
+        (ElicitationReply::Accept(form.clone()), format!("Elicitation accepted: {form}")),
+        (ElicitationReply::Decline, "Elicitation declined".to_string()),
+        (ElicitationReply::Cancel, "Elicitation cancelled".to_string()),
+    ];
+
+    for (reply, expected) in cases {
+        tracing::info!("Replying to elicitation with {reply:?}...");
+        ctx.handler.script_elicitation(reply);
+        let tool_result = call(&ctx, "elicit_user_info", serde_json::json!({})).await?;
+        let text = text_of(&tool_result)?;
+        ensure!(text == expected, "unexpected elicitation outcome: {text:?}, expected {expected:?}");
+        tracing::info!("✓ {expected}");
+    }
+    Ok(Outcome::Passed)
+}
+
+async fn progress(ctx: Context) -> Result<Outcome> {
+    if !ctx.has_tool("long_running_operation") {
+        return Ok(Outcome::missing_tools(&["long_running_operation"]));
+    }
+
+    let steps = 5;
+    let handle = ctx
+        .request(ctx.client.send_cancellable_request(
+            ClientRequest::CallToolRequest(CallToolRequest::new(CallToolRequestParam {
+                name: "long_running_operation".into(),
+                arguments: serde_json::json!({
+                    "steps": steps,
+                    "duration_ms": 500
+                })
+                .as_object()
+                .cloned(),
+            })),
+            PeerRequestOptions::no_options(),
+        ))
+        .await?;
+    let progress_token = handle.progress_token.clone();
+    let response = ctx.request(handle.await_response()).await?;
+    ensure!(
+        matches!(response, ServerResult::CallToolResult(ref r) if r.is_error != Some(true)),
+        "long_running_operation failed: {response:?}"
+    );
+
+    wait_for_notifications(|| ctx.handler.progress_for(&progress_token).len() >= steps).await;
+    // Arrival order is not send order, so check the values rather than the sequence
+    let mut progress = ctx.handler.progress_for(&progress_token);
+    progress.sort_by(|a, b| a.progress.total_cmp(&b.progress));
+    tracing::info!("Received {} progress notifications", progress.len());
+    for p in &progress {
+        tracing::info!("  - {}/{:?} {}", p.progress, p.total, p.message.as_deref().unwrap_or(""));
+    }
+    ensure!(
+        progress.len() == steps,
+        "expected {steps} progress notifications for {progress_token:?}, got {}",
+        progress.len()
+    );
+    ensure!(
+        progress.windows(2).all(|w| w[1].progress > w[0].progress),
+        "progress repeated a value instead of increasing"
+    );
+    ensure!(
+        progress.iter().all(|p| p.total.is_none_or(|total| p.progress <= total)),
+        "progress exceeded its declared total"
+    );
+    ensure!(
+        progress.last().is_some_and(|p| p.total == Some(p.progress)),
+        "final progress notification did not reach the declared total"
+    );
+    tracing::info!("✓ Progress notifications were monotonic and carried the request's token");
+    Ok(Outcome::Passed)
+}
+
+async fn cancellation(ctx: Context) -> Result<Outcome> {
+    if !(ctx.has_tool("slow_operation") && ctx.has_tool("get_operation_status")) {
+        return Ok(Outcome::missing_tools(&["slow_operation", "get_operation_status"]));
+    }
+
+    let operation_id = "e2e-cancel";
+    let duration = Duration::from_millis(2000);
+    let handle = ctx
+        .request(ctx.client.send_cancellable_request(
+            ClientRequest::CallToolRequest(CallToolRequest::new(CallToolRequestParam {
+                name: "slow_operation".into(),
+                arguments: serde_json::json!({
+                    "operation_id": operation_id,
+                    "duration_ms": duration.as_millis() as u64
+                })
+                .as_object()
+                .cloned(),
+            })),
+            PeerRequestOptions::no_options(),
+        ))
+        .await?;
+
+    // Let the server start the work before cancelling it. The notification is
+    // sent by hand rather than through `handle.cancel`, which would drop the
+    // response channel we still want to watch.
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let handle_id = handle.id.clone();
+    tracing::info!("Cancelling in-flight request {handle_id:?}...");
+    ctx.request(ctx.client.notify_cancelled(CancelledNotificationParam {
+        request_id: handle_id.clone(),
+        reason: Some("E2E cancellation test".to_string()),
+    }))
+    .await?;
+    let response = handle.await_response();
+
+    let mut status = operation_status(&ctx, operation_id).await?;
+    for _ in 0..20 {
+        if status != "running" {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        status = operation_status(&ctx, operation_id).await?;
+    }
+    ensure!(status == "cancelled", "server did not stop work after cancellation, status: {status}");
+    tracing::info!("✓ Server observed the cancellation and stopped work");
+
+    // rmcp ends the request locally as soon as the notification is sent; anything
+    // else means the server answered the cancelled id
+    match tokio::time::timeout(duration, response).await {
+        Err(_) | Ok(Err(ServiceError::Cancelled { .. })) => {}
+        Ok(Ok(result)) => bail!("cancelled request {handle_id:?} still got a result: {result:?}"),
+        Ok(Err(e)) => bail!("cancelled request {handle_id:?} still got a response: {e}"),
+    }
+    // Wait out the original duration; the server must not respond to the cancelled id at all
+    tokio::time::sleep(duration).await;
+    ensure!(
+        !ctx.handler.received_response(&handle_id),
+        "server sent a response for cancelled request {handle_id:?}"
+    );
+    let status = operation_status(&ctx, operation_id).await?;
+    ensure!(status == "cancelled", "cancelled operation later reported status {status}");
+    tracing::info!("✓ No late response or completion for the cancelled request");
+    Ok(Outcome::Passed)
+}
+
+async fn logging(ctx: Context) -> Result<Outcome> {
+    if ctx.server_info.as_ref().is_none_or(|info| info.capabilities.logging.is_none()) {
+        return Ok(Outcome::skipped("server does not advertise the logging capability"));
+    }
+
+    if !ctx.has_tool("emit_log_messages") {
+        return Ok(Outcome::missing_tools(&["emit_log_messages"]));
+    }
+
+    for threshold in [LoggingLevel::Debug, LoggingLevel::Warning, LoggingLevel::Error] {
+        ctx.request(ctx.client.set_level(SetLevelRequestParam { level: threshold })).await?;
+        ctx.handler.take_log_messages();
+
+        call(&ctx, "emit_log_messages", serde_json::json!({})).await?;
+
+        let mut expected: Vec<_> = LOG_LEVELS
+            .iter()
+            .copied()
+            .filter(|level| severity(*level) >= severity(threshold))
+            .collect();
+        let mut received = Vec::new();
+        wait_for_notifications(|| {
+            received.extend(ctx.handler.take_log_messages().into_iter().map(|m| m.level));
+            received.len() >= expected.len()
+        })
+        .await;
+        // Arrival order is not send order, so compare the levels as a multiset
+        received.sort_by_key(|level| severity(*level));
+        expected.sort_by_key(|level| severity(*level));
+        ensure!(
+            received == expected,
+            "at level {threshold:?} expected messages {expected:?}, got {received:?}"
+        );
+        tracing::info!("✓ Level {threshold:?}: received {} messages", received.len());
+    }
+    Ok(Outcome::Passed)
+}
+
+async fn dynamic_tools(ctx: Context) -> Result<Outcome> {
+    if !(ctx.has_tool("add_tool") && ctx.has_tool("remove_tool")) {
+        return Ok(Outcome::missing_tools(&["add_tool", "remove_tool"]));
+    }
+
+    let dynamic_name = "dynamic_e2e_tool";
+    let mut list_changed = ctx.handler.subscribe_tool_list_changed();
+    list_changed.mark_unchanged();
+
+    call(
+        &ctx,
+        "add_tool",
+        serde_json::json!({
+            "name": dynamic_name,
+            "description": "Registered by the Rust E2E client"
+        }),
+    )
+    .await?;
+    tokio::time::timeout(Duration::from_secs(5), list_changed.changed())
+        .await
+        .map_err(|_| anyhow::anyhow!("no tools/list_changed notification after add_tool"))??;
+    tracing::info!("✓ tools/list_changed received after add_tool");
+
+    let (relisted, _) = ctx.request(list_all_tool_pages(&ctx.client)).await?;
+    ensure!(
+        relisted.iter().any(|t| t.name == dynamic_name),
+        "{dynamic_name} missing from tools/list after add_tool"
+    );
+    let tool_result = call(&ctx, dynamic_name, serde_json::json!({})).await?;
+    ensure!(tool_result.is_error != Some(true), "calling {dynamic_name} failed: {tool_result:?}");
+    tracing::info!("✓ {dynamic_name} listed and callable");
+
+    list_changed.mark_unchanged();
+    call(&ctx, "remove_tool", serde_json::json!({ "name": dynamic_name })).await?;
+    tokio::time::timeout(Duration::from_secs(5), list_changed.changed())
+        .await
+        .map_err(|_| anyhow::anyhow!("no tools/list_changed notification after remove_tool"))??;
+    tracing::info!("✓ tools/list_changed received after remove_tool");
+
+    let (relisted, _) = ctx.request(list_all_tool_pages(&ctx.client)).await?;
+    ensure!(
+        !relisted.iter().any(|t| t.name == dynamic_name),
+        "{dynamic_name} still listed after remove_tool"
+    );
+    tracing::info!("✓ {dynamic_name} no longer listed");
+    Ok(Outcome::Passed)
+}
+
+async fn structured_output(ctx: Context) -> Result<Outcome> {
+    let cases = tool_cases(mcpbench_contract::contract(), |case| {
+        matches!(case.expect, ToolExpectation::Structured(_))
+    });
+    if !cases.iter().any(|(name, _)| ctx.has_tool(name)) {
+        let names: Vec<_> = cases.iter().map(|(name, _)| *name).collect();
+        return Ok(Outcome::missing_tools(&names));
+    }
+    if !negotiated_at_least(&ctx, STRUCTURED_OUTPUT_SINCE) {
+        return Ok(Outcome::skipped(format!(
+            "session negotiated {}; structured output needs {STRUCTURED_OUTPUT_SINCE}",
+            ctx.protocol_version().unwrap_or_default()
+        )));
+    }
+
+    for (name, case) in cases {
+        let Some(tool) = ctx.tool(name) else {
+            continue;
+        };
+        let output_schema = tool
+            .output_schema
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("{name} does not declare an outputSchema"))?;
+
+        let tool_result = call(&ctx, name, case.arguments.clone()).await?;
+        check::check_tool_response(Ok(tool_result.clone()), &case.expect)
+            .map_err(|e| anyhow::anyhow!("{name} [{}]: {e}", case.label))?;
+        let structured = tool_result
+            .structured_content
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("{name} returned no structuredContent: {tool_result:?}"))?;
+
+        let validator = jsonschema::validator_for(&serde_json::Value::Object((**output_schema).clone()))
+            .map_err(|e| anyhow::anyhow!("{name} declares an invalid outputSchema: {e}"))?;
+        let errors: Vec<_> = validator
+            .iter_errors(structured)
+            .map(|e| format!("{} at {}", e, e.instance_path))
+            .collect();
+        ensure!(errors.is_empty(), "{name} structuredContent violates its outputSchema: {errors:?}");
+
+        // Structured results should also be serialized into a text block for older clients
+        if let Some(text) = tool_result.content.first().and_then(|c| c.as_text()) {
+            let from_text: serde_json::Value = serde_json::from_str(&text.text)
+                .map_err(|e| anyhow::anyhow!("{name} text content is not the serialized structuredContent: {e}"))?;
+            ensure!(&from_text == structured, "{name} text content differs from structuredContent");
+        }
+        tracing::info!("✓ {name} structuredContent matches its outputSchema");
+    }
+    Ok(Outcome::Passed)
+}
+
+/// The fixture tools returning each kind of content block.
+const CONTENT_TOOLS: [&str; 5] =
+    ["get_image", "get_audio", "get_embedded_resource", "get_resource_link", "get_mixed_content"];
+
+async fn content_types(ctx: Context) -> Result<Outcome> {
+    if !CONTENT_TOOLS.iter().any(|name| ctx.has_tool(name)) {
+        return Ok(Outcome::skipped("server exposes none of the content-type fixture tools"));
+    }
+
+    // The versioned contract leaves out content the session's revision does not define
+    let cases = tool_cases(versioned_contract(&ctx), |case| {
+        !matches!(case.expect, ToolExpectation::InvalidParams { .. })
+    });
+    for (name, case) in cases {
+        if !CONTENT_TOOLS.contains(&name) || !ctx.has_tool(name) {
+            continue;
+        }
+        let tool_result = call(&ctx, name, case.arguments.clone()).await?;
+        check::check_tool_response(Ok(tool_result), &case.expect)
+            .map_err(|e| anyhow::anyhow!("{name} {}: {e}", case.arguments))?;
+        tracing::info!("✓ {name} {}", case.arguments);
+    }
+    Ok(Outcome::Passed)
+}
+
+async fn argument_validation(ctx: Context) -> Result<Outcome> {
+    let cases = tool_cases(mcpbench_contract::contract(), |case| {
+        matches!(case.expect, ToolExpectation::InvalidParams { .. })
+    });
+    if !cases.iter().any(|(name, _)| ctx.has_tool(name)) {
+        return Ok(Outcome::skipped("server exposes none of the tools with invalid-argument cases"));
+    }
+
+    for (name, case) in cases {
+        if !ctx.has_tool(name) {
+            continue;
+        }
+        let response = respond(
+            &ctx,
+            ctx.client.call_tool(CallToolRequestParam {
+                name: name.into(),
+                arguments: case.arguments.as_object().cloned(),
+            }),
+        )
+        .await?;
+        check::check_tool_response(response, &case.expect)
+            .map_err(|e| anyhow::anyhow!("{name} {}: {e}", case.arguments))?;
+        tracing::info!("✓ {name} {} rejected", case.arguments);
+    }
+    Ok(Outcome::Passed)
+}
+
+async fn contract(ctx: Context) -> Result<Outcome> {
+    use mcpbench_contract::Status;
+
+    let report = mcpbench_contract::check(&ctx.client, &versioned_contract(&ctx)).await;
+    for item in &report.items {
+        let mark = match item.status {
+            Status::Pass => "✓",
+            Status::Fail => "✗",
+            Status::Missing => "-",
+        };
+        let case = if item.case.is_empty() { String::new() } else { format!(" [{}]", item.case) };
+        match &item.detail {
+            Some(detail) => tracing::info!("{mark} {:?} {}{case}: {detail}", item.kind, item.name),
+            None => tracing::info!("{mark} {:?} {}{case}", item.kind, item.name),
+        }
+    }
+    tracing::info!(
+        "Contract: {} passed, {} failed, {} missing",
+        report.count(Status::Pass),
+        report.count(Status::Fail),
+        report.count(Status::Missing)
+    );
+    ensure!(
+        report.count(Status::Fail) == 0,
+        "server does not conform to the fixture contract ({} failing items)",
+        report.count(Status::Fail)
+    );
+    Ok(Outcome::Passed)
+}
+
+/// The fixture contract, without what the main session's revision does not define.
+fn versioned_contract(ctx: &Context) -> Contract {
+    let contract = mcpbench_contract::contract();
+    match ctx.protocol_version() {
+        Some(version) => contract.for_version(&version),
+        None => contract,
+    }
+}
+
+/// The contract's tool cases that satisfy `keep`, each with its tool's name.
+fn tool_cases(contract: Contract, keep: impl Fn(&ToolCase) -> bool) -> Vec<(&'static str, ToolCase)> {
+    contract
+        .tools
+        .into_iter()
+        .flat_map(|tool| tool.cases.into_iter().map(move |case| (tool.name, case)))
+        .filter(|(_, case)| keep(case))
+        .collect()
+}
+
+/// Awaits `request` within the per-request timeout, keeping the SDK error so
+/// the contract's checks can tell an `invalid_params` error from a failure.
+async fn respond<T>(
+    ctx: &Context,
+    request: impl Future<Output = Result<T, ServiceError>>,
+) -> Result<Result<T, ServiceError>> {
+    tokio::time::timeout(ctx.request_timeout, request)
+        .await
+        .map_err(|_| anyhow::anyhow!("no response within {:?}", ctx.request_timeout))
+}
+
+/// Whether the main session negotiated `since` or a later revision.
+fn negotiated_at_least(ctx: &Context, since: &str) -> bool {
+    // Dates sort lexically, so this is a version comparison
+    ctx.protocol_version().is_none_or(|version| version.as_str() >= since)
+}
+
+async fn call(ctx: &Context, name: &str, arguments: serde_json::Value) -> Result<CallToolResult> {
+    let result = ctx
+        .request(ctx.client.call_tool(CallToolRequestParam {
+            name: name.to_string().into(),
+            arguments: arguments.as_object().cloned(),
+        }))
+        .await?;
+    ensure!(result.is_error != Some(true), "{name} returned an error: {:?}", result.content);
+    Ok(result)
+}
+
+/// The text of the first content block, which must be text.
+fn text_of(result: &CallToolResult) -> Result<&str> {
+    result
+        .content
+        .first()
+        .and_then(|c| c.as_text())
+        .map(|t| t.text.as_str())
+        .ok_or_else(|| anyhow::anyhow!("expected a text content block, got {:?}", result.content))
+}
+
+/// Increments the session counter by `value` and returns the new value.
+async fn counter_after(ctx: &Context, value: i64) -> Result<i64> {
+    let tool_result = call(ctx, "increment", serde_json::json!({ "value": value })).await?;
+    let text = text_of(&tool_result)?;
+    let expected_prefix = format!("Counter incremented by {value}. New value: ");
+    text.strip_prefix(&expected_prefix)
+        .and_then(|n| n.parse().ok())
+        .ok_or_else(|| anyhow::anyhow!("unexpected increment reply: {text:?}"))
+}
+
+/// Upper bound on `tools/list` pages, guarding against servers that never stop paginating.
+const MAX_TOOL_PAGES: usize = 1000;
+
+/// Lists tools by following `next_cursor` to the end, returning the tools and page count.
+pub async fn list_all_tool_pages(client: &Peer<RoleClient>) -> Result<(Vec<Tool>, usize)> {
+    let mut tools = Vec::new();
+    let mut cursor = None;
+    for page in 1..=MAX_TOOL_PAGES {
+        let result = client.list_tools(Some(PaginatedRequestParam { cursor })).await?;
+        tools.extend(result.tools);
+        match result.next_cursor {
+            Some(next) => cursor = Some(next),
+            None => return Ok((tools, page)),
+        }
+    }
+    bail!("tools/list did not finish after {MAX_TOOL_PAGES} pages")
+}
+
+async fn call_list_roots(ctx: &Context) -> Result<serde_json::Value> {
+    let result = call(ctx, "list_roots", serde_json::json!({})).await?;
+    Ok(serde_json::from_str(text_of(&result)?)?)
+}
+
+fn root_uris(seen: &serde_json::Value) -> Vec<&str> {
+    seen["roots"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|r| r["uri"].as_str())
+        .collect()
+}
+
+/// How long to wait for notifications that trail their request's response.
+const NOTIFICATION_WAIT: Duration = Duration::from_secs(5);
+
+/// rmcp handles every notification on its own task, so some may still be in
+/// flight after the response that followed them. Polls `done` until it holds
+/// or [`NOTIFICATION_WAIT`] elapses; callers assert on what arrived either way.
+async fn wait_for_notifications(mut done: impl FnMut() -> bool) {
+    let deadline = tokio::time::Instant::now() + NOTIFICATION_WAIT;
+    while !done() && tokio::time::Instant::now() < deadline {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+}
+
+async fn operation_status(ctx: &Context, operation_id: &str) -> Result<String> {
+    let result = call(ctx, "get_operation_status", serde_json::json!({ "operation_id": operation_id })).await?;
+    Ok(text_of(&result)?.to_string())
+}
@@ -0,0 +1,47 @@
+//! Extra sessions opened by scenarios that need their own `initialize`, such
+//! as requesting a specific protocol version.
+
+use anyhow::Result;
+use rmcp::{RoleClient, service::RunningService};
+
+use crate::{SERVER_EXIT_GRACE, handler::E2eClient, process::ServerProcess, report, runner::Context};
+
+/// A second connection to the server under test, alongside the main one.
+pub struct Session {
+    pub client: RunningService<RoleClient, E2eClient>,
+    process: Option<ServerProcess>,
+}
+
+impl Session {
+    /// Connects like the main session did, over the same transport, but
+    /// requesting `protocol_version` in `initialize`. A stdio server is spawned
+    /// afresh and stopped again by [`Session::close`].
+    pub async fn open(ctx: &Context, protocol_version: &str) -> Result<Self> {
+        let mut args = (*ctx.connect).clone();
+        args.protocol_version = Some(protocol_version.to_string());
+        let mut process = None;
+        match crate::connect(&args, crate::client_info(&args)?, &mut process).await {
+            Ok((client, _)) => Ok(Self { client, process }),
+            Err(e) => {
+                if let Some(mut process) = process {
+                    process.shutdown(SERVER_EXIT_GRACE).await;
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// The version the server answered `initialize` with, as sent on the wire.
+    pub fn protocol_version(&self) -> Option<String> {
+        self.client.peer_info().map(report::protocol_version)
+    }
+
+    pub async fn close(self) {
+        if let Err(e) = self.client.cancel().await {
+            tracing::warn!("Closing extra session failed: {e}");
+        }
+        if let Some(mut process) = self.process {
+            process.shutdown(SERVER_EXIT_GRACE).await;
+        }
+    }
+}
@@ -0,0 +1,51 @@
+//! Streamable HTTP → legacy SSE fallback, as described in the spec's
+//! backwards-compatibility section.
+//!
+//! The spec has the client POST `initialize` and fall back when that fails.
+//! Probing with `initialize` would leave the server with a session the real
+//! client never uses, so this POSTs a `ping` instead: a streamable HTTP server
+//! routes it and refuses it for lacking a session, while a legacy HTTP+SSE
+//! server has no `POST` route at the URL and answers `404` or `405`. Only those
+//! two statuses switch the client to a `GET` on the same URL expecting the
+//! legacy SSE stream.
+
+use std::time::Duration;
+
+use anyhow::{Context as _, Result};
+use reqwest::{
+    StatusCode,
+    header::{ACCEPT, CONTENT_TYPE},
+};
+
+use crate::cli::TransportKind;
+
+/// Decides which transport `url` speaks without opening a session on it.
+/// Network errors are returned rather than treated as a reason to fall back.
+///
+/// The probe runs before the real session is opened because rmcp's transport
+/// errors do not expose the HTTP status.
+pub async fn negotiate(url: &str, timeout: Duration) -> Result<TransportKind> {
+    let http = reqwest::Client::builder().timeout(timeout).build()?;
+    let body = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "ping",
+    });
+    let response = http
+        .post(url)
+        .header(ACCEPT, "application/json, text/event-stream")
+        .header(CONTENT_TYPE, "application/json")
+        .body(serde_json::to_vec(&body)?)
+        .send()
+        .await
+        .with_context(|| format!("probing {url} for streamable HTTP"))?;
+
+    let status = response.status();
+    if matches!(status, StatusCode::NOT_FOUND | StatusCode::METHOD_NOT_ALLOWED) {
+        tracing::info!("Streamable HTTP POST rejected with {status}, falling back to HTTP+SSE");
+        Ok(TransportKind::Sse)
+    } else {
+        tracing::info!("Streamable HTTP POST answered with {status}");
+        Ok(TransportKind::StreamableHttp)
+    }
+}
@@ -0,0 +1,380 @@
+//! The canonical contract every e2e server must satisfy.
+//!
+//! Scenarios that need the client to answer server-initiated requests
+//! (sampling, roots, elicitation) are exercised by the client's own scenarios
+//! and are deliberately not part of this data.
+
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use rmcp::model::{PromptMessageRole, Role};
+use serde_json::json;
+
+use crate::{
+    fixtures::{self, BYTES_URI, GREETING_TEXT, GREETING_URI, README_TEXT, README_URI},
+    spec::{
+        Contract, ExpectedAnnotations, ExpectedBlock, ExpectedContent, ExpectedMessage, PromptCase, PromptContract,
+        PromptExpectation, ResourceContract, ResourceExpectation, ResourceSource, ToolCase, ToolContract,
+        ToolExpectation,
+    },
+};
+
+pub fn contract() -> Contract {
+    Contract {
+        tools: tools(),
+        resources: resources(),
+        prompts: prompts(),
+    }
+}
+
+fn case(label: &'static str, arguments: serde_json::Value, expect: ToolExpectation) -> ToolCase {
+    ToolCase { label, arguments, expect }
+}
+
+fn invalid(label: &'static str, arguments: serde_json::Value, path: &'static str) -> ToolCase {
+    case(label, arguments, ToolExpectation::InvalidParams { path })
+}
+
+fn block(expect: ToolExpectation) -> ExpectedBlock {
+    ExpectedBlock { expect, annotations: None }
+}
+
+fn annotated(expect: ToolExpectation, audience: Role, priority: f32) -> ExpectedBlock {
+    ExpectedBlock {
+        expect,
+        annotations: Some(ExpectedAnnotations {
+            audience: vec![audience],
+            priority,
+        }),
+    }
+}
+
+fn png() -> ToolExpectation {
+    ToolExpectation::Image {
+        mime_type: "image/png",
+        bytes: STANDARD.decode(fixtures::PNG_BASE64).expect("PNG fixture is valid base64"),
+    }
+}
+
+fn wav() -> ToolExpectation {
+    ToolExpectation::Audio {
+        mime_type: "audio/wav",
+        bytes: fixtures::wav_bytes(),
+    }
+}
+
+fn greeting() -> ToolExpectation {
+    ToolExpectation::EmbeddedText {
+        uri: GREETING_URI,
+        mime_type: "text/plain",
+        text: GREETING_TEXT.to_string(),
+    }
+}
+
+fn readme_link() -> ToolExpectation {
+    ToolExpectation::ResourceLink {
+        uri: README_URI,
+        mime_type: "text/markdown",
+    }
+}
+
+fn tools() -> Vec<ToolContract> {
+    vec![
+        ToolContract {
+            name: "send_message",
+            cases: vec![
+                case(
+                    "echo",
+                    json!({ "message": "Hello from the contract checker" }),
+                    ToolExpectation::Text("Received message: Hello from the contract checker".to_string()),
+                ),
+                invalid("missing message", json!({}), "/message"),
+                invalid("non-string message", json!({ "message": 42 }), "/message"),
+            ],
+        },
+        ToolContract {
+            name: "get_server_info",
+            cases: vec![case(
+                "info",
+                json!({}),
+                ToolExpectation::TextPrefix("Server Information: ".to_string()),
+            )],
+        },
+        ToolContract {
+            name: "increment",
+            cases: vec![case(
+                "increment by 1",
+                json!({ "value": 1 }),
+                ToolExpectation::TextPrefix("Counter incremented by 1. New value: ".to_string()),
+            )],
+        },
+        ToolContract {
+            name: "long_running_operation",
+            cases: vec![
+                case(
+                    "two steps",
+                    json!({ "steps": 2, "duration_ms": 0 }),
+                    ToolExpectation::Text("Long running operation completed: 2 steps".to_string()),
+                ),
+                invalid("zero steps", json!({ "steps": 0 }), "/steps"),
+            ],
+        },
+        ToolContract {
+            name: "get_operation_status",
+            cases: vec![case(
+                "unknown operation",
+                json!({ "operation_id": "contract-unknown" }),
+                ToolExpectation::Text("unknown".to_string()),
+            )],
+        },
+        ToolContract {
+            name: "get_weather",
+            cases: vec![case(
+                "Paris",
+                json!({ "city": "Paris" }),
+                ToolExpectation::Structured(json!({
+                    "city": "Paris",
+                    "temperature": 22.5,
+                    "conditions": "Sunny",
+                    "humidity": 65
+                })),
+            )],
+        },
+        ToolContract {
+            name: "calculate_stats",
+            cases: vec![
+                case(
+                    "1..4",
+                    json!({ "numbers": [1, 2, 3, 4] }),
+                    ToolExpectation::Structured(json!({ "count": 4, "sum": 10, "mean": 2.5, "min": 1, "max": 4 })),
+                ),
+                invalid("non-numeric item", json!({ "numbers": [1, "two"] }), "/numbers/1"),
+            ],
+        },
+        ToolContract {
+            name: "get_image",
+            cases: vec![case("png", json!({}), png())],
+        },
+        ToolContract {
+            name: "get_audio",
+            cases: vec![case("wav", json!({}), wav())],
+        },
+        ToolContract {
+            name: "get_embedded_resource",
+            cases: vec![
+                case("text", json!({ "kind": "text" }), greeting()),
+                case(
+                    "blob",
+                    json!({ "kind": "blob" }),
+                    ToolExpectation::EmbeddedBlob {
+                        uri: BYTES_URI,
+                        mime_type: "application/octet-stream",
+                        bytes: fixtures::fixture_bytes(),
+                    },
+                ),
+                invalid("unknown kind", json!({ "kind": "video" }), "/kind"),
+            ],
+        },
+        ToolContract {
+            name: "get_resource_link",
+            cases: vec![case("readme", json!({}), readme_link())],
+        },
+        ToolContract {
+            name: "get_mixed_content",
+            cases: vec![case(
+                "every block type",
+                json!({}),
+                ToolExpectation::Blocks(vec![
+                    annotated(ToolExpectation::Text("Mixed content response".to_string()), Role::User, 1.0),
+                    annotated(png(), Role::Assistant, 0.5),
+                    block(wav()),
+                    block(greeting()),
+                    block(readme_link()),
+                ]),
+            )],
+        },
+    ]
+}
+
+fn resources() -> Vec<ResourceContract> {
+    vec![
+        ResourceContract {
+            uri: GREETING_URI.to_string(),
+            source: ResourceSource::Static,
+            expect: ResourceExpectation::Text {
+                mime_type: "text/plain",
+                text: GREETING_TEXT.to_string(),
+            },
+        },
+        ResourceContract {
+            uri: README_URI.to_string(),
+            source: ResourceSource::Static,
+            expect: ResourceExpectation::Text {
+                mime_type: "text/markdown",
+                text: README_TEXT.to_string(),
+            },
+        },
+        ResourceContract {
+            uri: BYTES_URI.to_string(),
+            source: ResourceSource::Static,
+            expect: ResourceExpectation::Blob {
+                mime_type: "application/octet-stream",
+                bytes: fixtures::fixture_bytes(),
+            },
+        },
+        ResourceContract {
+            uri: "test://echo/contract".to_string(),
+            source: ResourceSource::Template(fixtures::ECHO_TEMPLATE),
+            expect: ResourceExpectation::Text {
+                mime_type: "text/plain",
+                text: "contract".to_string(),
+            },
+        },
+        ResourceContract {
+            uri: "test://pattern/300".to_string(),
+            source: ResourceSource::Template(fixtures::PATTERN_TEMPLATE),
+            expect: ResourceExpectation::Blob {
+                mime_type: "application/octet-stream",
+                bytes: fixtures::pattern_bytes(300),
+            },
+        },
+    ]
+}
+
+fn text(role: PromptMessageRole, text: &str) -> ExpectedMessage {
+    ExpectedMessage {
+        role,
+        content: ExpectedContent::Text(text.to_string()),
+    }
+}
+
+fn prompt_case(label: &'static str, arguments: serde_json::Value, messages: Vec<ExpectedMessage>) -> PromptCase {
+    PromptCase {
+        label,
+        arguments,
+        expect: PromptExpectation::Messages(messages),
+    }
+}
+
+fn prompts() -> Vec<PromptContract> {
+    vec![
+        PromptContract {
+            name: "simple_prompt",
+            cases: vec![prompt_case(
+                "no arguments",
+                json!({}),
+                vec![text(PromptMessageRole::User, "This is a simple prompt without arguments.")],
+            )],
+        },
+        PromptContract {
+            name: "greeting_prompt",
+            cases: vec![
+                prompt_case(
+                    "with name",
+                    json!({ "name": "Contract" }),
+                    vec![text(PromptMessageRole::User, "Hello, Contract!")],
+                ),
+                PromptCase {
+                    label: "missing name",
+                    arguments: json!({}),
+                    expect: PromptExpectation::Error,
+                },
+            ],
+        },
+        PromptContract {
+            name: "styled_prompt",
+            cases: vec![
+                prompt_case(
+                    "default style",
+                    json!({ "topic": "MCP" }),
+                    vec![text(PromptMessageRole::User, "Explain MCP in a concise style.")],
+                ),
+                prompt_case(
+                    "explicit style",
+                    json!({ "topic": "MCP", "style": "formal" }),
+                    vec![text(PromptMessageRole::User, "Explain MCP in a formal style.")],
+                ),
+            ],
+        },
+        PromptContract {
+            name: "conversation_prompt",
+            cases: vec![prompt_case(
+                "embedded resource",
+                json!({}),
+                vec![
+                    text(PromptMessageRole::User, "What does the greeting resource say?"),
+                    text(PromptMessageRole::Assistant, "Here is the greeting resource:"),
+                    ExpectedMessage {
+                        role: PromptMessageRole::Assistant,
+                        content: ExpectedContent::EmbeddedText {
+                            uri: GREETING_URI,
+                            text: GREETING_TEXT.to_string(),
+                        },
+                    },
+                    text(PromptMessageRole::User, "Thanks!"),
+                ],
+            )],
+        },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn names_and_uris_are_unique() {
+        let contract = contract();
+        let tools: HashSet<_> = contract.tools.iter().map(|tool| tool.name).collect();
+        assert_eq!(tools.len(), contract.tools.len());
+        let prompts: HashSet<_> = contract.prompts.iter().map(|prompt| prompt.name).collect();
+        assert_eq!(prompts.len(), contract.prompts.len());
+        let uris: HashSet<_> = contract.resources.iter().map(|resource| resource.uri.as_str()).collect();
+        assert_eq!(uris.len(), contract.resources.len());
+    }
+
+    #[test]
+    fn every_item_has_uniquely_labelled_cases() {
+        let contract = contract();
+        for tool in &contract.tools {
+            let labels: HashSet<_> = tool.cases.iter().map(|case| case.label).collect();
+            assert!(!tool.cases.is_empty(), "{} has no cases", tool.name);
+            assert_eq!(labels.len(), tool.cases.len(), "{} repeats a case label", tool.name);
+        }
+        for prompt in &contract.prompts {
+            let labels: HashSet<_> = prompt.cases.iter().map(|case| case.label).collect();
+            assert!(!prompt.cases.is_empty(), "{} has no cases", prompt.name);
+            assert_eq!(labels.len(), prompt.cases.len(), "{} repeats a case label", prompt.name);
+        }
+    }
+
+    #[test]
+    fn older_versions_drop_cases_they_do_not_define() {
+        let has_tool = |contract: &Contract, tool: &str| contract.tools.iter().any(|t| t.name == tool);
+        let latest = contract().for_version("2025-06-18");
+        assert_eq!(latest.tools.len(), contract().tools.len());
+
+        let older = contract().for_version("2025-03-26");
+        assert!(has_tool(&older, "get_audio"));
+        assert!(!has_tool(&older, "get_resource_link"));
+        assert!(!has_tool(&older, "get_weather"));
+        // Mixed content includes a resource link
+        assert!(!has_tool(&older, "get_mixed_content"));
+        // Invalid-argument cases are defined by every revision
+        assert!(has_tool(&older, "calculate_stats"));
+
+        let oldest = contract().for_version("2024-11-05");
+        assert!(!has_tool(&oldest, "get_audio"));
+        assert!(has_tool(&oldest, "get_image"));
+    }
+
+    #[test]
+    fn templated_resources_match_their_template() {
+        for resource in contract().resources {
+            if let ResourceSource::Template(template) = resource.source {
+                let prefix = &template[..template.find('{').expect("template has a variable")];
+                assert!(resource.uri.starts_with(prefix), "{} is not produced by {template}", resource.uri);
+            }
+        }
+    }
+}
@@ -0,0 +1,453 @@
+//! Runs a [`Contract`] against a connected server.
+
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use rmcp::{
+    Peer, RoleClient, ServiceError,
+    model::{
+        CallToolRequestParam, CallToolResult, Content, ErrorCode, GetPromptRequestParam, GetPromptResult,
+        PromptMessageContent, RawContent, ReadResourceRequestParam, ReadResourceResult, ResourceContents,
+    },
+};
+use serde_json::Value;
+
+use crate::{
+    report::{ConformanceReport, ItemKind},
+    spec::{
+        Contract, ExpectedAnnotations, ExpectedContent, ExpectedMessage, PromptExpectation, ResourceContract,
+        ResourceExpectation, ResourceSource, ToolCase, ToolExpectation,
+    },
+};
+
+/// The result of one check: `Err` carries why it failed.
+pub type Outcome = Result<(), String>;
+
+macro_rules! ensure {
+    ($cond:expr, $($arg:tt)+) => {
+        if !$cond {
+            return Err(format!($($arg)+));
+        }
+    };
+}
+
+/// Checks every tool, resource and prompt in `contract`.
+///
+/// A server without a capability answers its list request with
+/// `method_not_found`, and that category's items are reported as
+/// [`Status::Missing`](crate::Status::Missing). Any other listing failure is
+/// recorded as one [`Status::Fail`](crate::Status::Fail) item for the category;
+/// the remaining categories are still checked.
+pub async fn check(peer: &Peer<RoleClient>, contract: &Contract) -> ConformanceReport {
+    let mut report = ConformanceReport::default();
+    check_tools(peer, contract, &mut report).await;
+    check_resources(peer, contract, &mut report).await;
+    check_prompts(peer, contract, &mut report).await;
+    report
+}
+
+async fn check_tools(peer: &Peer<RoleClient>, contract: &Contract, report: &mut ConformanceReport) {
+    let Some(listed) = listing(peer.list_all_tools().await, ItemKind::Tool, "tools/list", report) else {
+        return;
+    };
+    for tool in &contract.tools {
+        if !listed.iter().any(|t| t.name == tool.name) {
+            for case in &tool.cases {
+                report.push_missing(ItemKind::Tool, tool.name, case.label);
+            }
+            continue;
+        }
+        for case in &tool.cases {
+            let outcome = check_tool_case(peer, tool.name, case).await;
+            report.push(ItemKind::Tool, tool.name, case.label, outcome);
+        }
+    }
+}
+
+/// The listed items, treating `method_not_found` as an empty listing. Other
+/// errors are pushed as a failed item named after `method` and yield `None`.
+fn listing<T>(
+    result: Result<Vec<T>, ServiceError>,
+    kind: ItemKind,
+    method: &str,
+    report: &mut ConformanceReport,
+) -> Option<Vec<T>> {
+    match result {
+        Ok(items) => Some(items),
+        Err(ServiceError::McpError(error)) if error.code == ErrorCode::METHOD_NOT_FOUND => Some(Vec::new()),
+        Err(e) => {
+            report.push(kind, method, "", Err(format!("listing failed: {e}")));
+            None
+        }
+    }
+}
+
+async fn check_tool_case(peer: &Peer<RoleClient>, name: &str, case: &ToolCase) -> Outcome {
+    let response = peer
+        .call_tool(CallToolRequestParam {
+            name: name.to_string().into(),
+            arguments: case.arguments.as_object().cloned(),
+        })
+        .await;
+    check_tool_response(response, &case.expect)
+}
+
+/// Checks a `tools/call` response against one case's expectation.
+pub fn check_tool_response(response: Result<CallToolResult, ServiceError>, expect: &ToolExpectation) -> Outcome {
+    if let ToolExpectation::InvalidParams { path } = expect {
+        let error = match response {
+            Err(ServiceError::McpError(error)) => error,
+            Err(other) => return Err(format!("expected an invalid_params error, got {other}")),
+            Ok(result) => return Err(format!("server accepted invalid arguments: {result:?}")),
+        };
+        ensure!(
+            error.code == ErrorCode::INVALID_PARAMS,
+            "expected error code {}, got {}",
+            ErrorCode::INVALID_PARAMS.0,
+            error.code.0
+        );
+        let paths: Option<Vec<_>> = error
+            .data
+            .as_ref()
+            .and_then(|data| data["errors"].as_array())
+            .map(|errors| errors.iter().filter_map(|e| e["path"].as_str()).collect());
+        if let Some(paths) = paths {
+            ensure!(paths.contains(path), "expected an error at {path}, got {paths:?} ({})", error.message);
+        }
+        return Ok(());
+    }
+
+    let result = response.map_err(|e| format!("call failed: {e}"))?;
+    ensure!(result.is_error != Some(true), "tool reported an error: {:?}", result.content);
+    match expect {
+        ToolExpectation::Structured(expected) => {
+            let actual = result
+                .structured_content
+                .as_ref()
+                .ok_or_else(|| "missing structuredContent".to_string())?;
+            ensure!(json_matches(actual, expected), "expected {expected}, got {actual}");
+        }
+        ToolExpectation::Blocks(expected) => {
+            ensure!(
+                result.content.len() == expected.len(),
+                "expected {} content blocks, got {}",
+                expected.len(),
+                result.content.len()
+            );
+            for (index, (content, block)) in result.content.iter().zip(expected).enumerate() {
+                check_content(content, &block.expect).map_err(|e| format!("block {index}: {e}"))?;
+                if let Some(annotations) = &block.annotations {
+                    check_annotations(content, annotations).map_err(|e| format!("block {index}: {e}"))?;
+                }
+            }
+        }
+        ToolExpectation::InvalidParams { .. } => unreachable!("handled above"),
+        single_block => check_content(single(&result)?, single_block)?,
+    }
+    Ok(())
+}
+
+/// Checks one content block against a single-block expectation.
+fn check_content(content: &Content, expect: &ToolExpectation) -> Outcome {
+    match (expect, &content.raw) {
+        (ToolExpectation::Text(expected), RawContent::Text(text)) => {
+            ensure!(text.text == *expected, "expected {expected:?}, got {:?}", text.text);
+        }
+        (ToolExpectation::TextPrefix(prefix), RawContent::Text(text)) => {
+            ensure!(
+                text.text.starts_with(prefix.as_str()),
+                "expected text starting with {prefix:?}, got {:?}",
+                text.text
+            );
+        }
+        (ToolExpectation::Image { mime_type, bytes }, RawContent::Image(image)) => {
+            ensure!(image.mime_type == *mime_type, "unexpected MIME type {}", image.mime_type);
+            ensure!(decode(&image.data)? == *bytes, "image bytes differ from fixture");
+        }
+        (ToolExpectation::Audio { mime_type, bytes }, RawContent::Audio(audio)) => {
+            ensure!(audio.mime_type == *mime_type, "unexpected MIME type {}", audio.mime_type);
+            ensure!(decode(&audio.data)? == *bytes, "audio bytes differ from fixture");
+        }
+        (ToolExpectation::EmbeddedText { uri, mime_type, text }, RawContent::Resource(embedded)) => {
+            check_resource_contents(&embedded.resource, uri, &ResourceExpectation::Text {
+                mime_type,
+                text: text.clone(),
+            })?
+        }
+        (ToolExpectation::EmbeddedBlob { uri, mime_type, bytes }, RawContent::Resource(embedded)) => {
+            check_resource_contents(&embedded.resource, uri, &ResourceExpectation::Blob {
+                mime_type,
+                bytes: bytes.clone(),
+            })?
+        }
+        (ToolExpectation::ResourceLink { uri, mime_type }, RawContent::ResourceLink(link)) => {
+            ensure!(link.uri == *uri, "unexpected resource link uri {}", link.uri);
+            ensure!(
+                link.mime_type.as_deref() == Some(*mime_type),
+                "unexpected resource link MIME type {:?}",
+                link.mime_type
+            );
+        }
+        (ToolExpectation::Structured(_) | ToolExpectation::Blocks(_) | ToolExpectation::InvalidParams { .. }, _) => {
+            return Err(format!("{expect:?} does not describe a single content block"));
+        }
+        (expect, other) => return Err(format!("expected {}, got {other:?}", block_kind(expect))),
+    }
+    Ok(())
+}
+
+fn block_kind(expect: &ToolExpectation) -> &'static str {
+    match expect {
+        ToolExpectation::Text(_) | ToolExpectation::TextPrefix(_) => "text content",
+        ToolExpectation::Image { .. } => "image content",
+        ToolExpectation::Audio { .. } => "audio content",
+        ToolExpectation::EmbeddedText { .. } | ToolExpectation::EmbeddedBlob { .. } => "an embedded resource",
+        ToolExpectation::ResourceLink { .. } => "a resource link",
+        _ => "a single content block",
+    }
+}
+
+fn check_annotations(content: &Content, expected: &ExpectedAnnotations) -> Outcome {
+    let annotations = content
+        .annotations
+        .as_ref()
+        .ok_or_else(|| format!("missing annotations on {:?}", content.raw))?;
+    ensure!(
+        annotations.audience.as_deref() == Some(expected.audience.as_slice()),
+        "unexpected audience {:?}",
+        annotations.audience
+    );
+    ensure!(annotations.priority == Some(expected.priority), "unexpected priority {:?}", annotations.priority);
+    Ok(())
+}
+
+async fn check_resources(peer: &Peer<RoleClient>, contract: &Contract, report: &mut ConformanceReport) {
+    let Some(resources) = listing(peer.list_all_resources().await, ItemKind::Resource, "resources/list", report)
+    else {
+        return;
+    };
+    let templates = peer.list_all_resource_templates().await;
+    let Some(templates) = listing(templates, ItemKind::Resource, "resources/templates/list", report) else {
+        return;
+    };
+    for resource in &contract.resources {
+        let listed = match resource.source {
+            ResourceSource::Static => resources.iter().any(|r| r.uri == resource.uri),
+            ResourceSource::Template(template) => templates.iter().any(|t| t.uri_template == template),
+        };
+        if !listed {
+            report.push_missing(ItemKind::Resource, resource.uri.as_str(), "");
+            continue;
+        }
+        let response = peer
+            .read_resource(ReadResourceRequestParam { uri: resource.uri.clone() })
+            .await;
+        let outcome = check_read_response(response, resource);
+        report.push(ItemKind::Resource, resource.uri.as_str(), "", outcome);
+    }
+}
+
+/// Checks a `resources/read` response for `resource.uri` against its expectation.
+pub fn check_read_response(response: Result<ReadResourceResult, ServiceError>, resource: &ResourceContract) -> Outcome {
+    match response {
+        Ok(result) => match result.contents.as_slice() {
+            [contents] => check_resource_contents(contents, &resource.uri, &resource.expect),
+            contents => Err(format!("expected exactly one content item, got {}", contents.len())),
+        },
+        Err(e) => Err(format!("read failed: {e}")),
+    }
+}
+
+fn check_resource_contents(contents: &ResourceContents, uri: &str, expect: &ResourceExpectation) -> Outcome {
+    match (contents, expect) {
+        (
+            ResourceContents::TextResourceContents { uri: actual_uri, mime_type, text, .. },
+            ResourceExpectation::Text { mime_type: expected_mime, text: expected },
+        ) => {
+            ensure!(actual_uri == uri, "unexpected uri {actual_uri}");
+            ensure!(mime_type.as_deref() == Some(*expected_mime), "unexpected MIME type {mime_type:?}");
+            ensure!(text == expected, "expected {expected:?}, got {text:?}");
+        }
+        (
+            ResourceContents::BlobResourceContents { uri: actual_uri, mime_type, blob, .. },
+            ResourceExpectation::Blob { mime_type: expected_mime, bytes },
+        ) => {
+            ensure!(actual_uri == uri, "unexpected uri {actual_uri}");
+            ensure!(mime_type.as_deref() == Some(*expected_mime), "unexpected MIME type {mime_type:?}");
+            let actual = decode(blob)?;
+            ensure!(actual == *bytes, "blob differs from fixture ({} bytes, expected {})", actual.len(), bytes.len());
+        }
+        (ResourceContents::TextResourceContents { .. }, ResourceExpectation::Blob { .. }) => {
+            return Err("expected blob contents, got text".to_string());
+        }
+        (ResourceContents::BlobResourceContents { .. }, ResourceExpectation::Text { .. }) => {
+            return Err("expected text contents, got blob".to_string());
+        }
+    }
+    Ok(())
+}
+
+async fn check_prompts(peer: &Peer<RoleClient>, contract: &Contract, report: &mut ConformanceReport) {
+    let Some(listed) = listing(peer.list_all_prompts().await, ItemKind::Prompt, "prompts/list", report) else {
+        return;
+    };
+    for prompt in &contract.prompts {
+        if !listed.iter().any(|p| p.name == prompt.name) {
+            for case in &prompt.cases {
+                report.push_missing(ItemKind::Prompt, prompt.name, case.label);
+            }
+            continue;
+        }
+        for case in &prompt.cases {
+            let response = peer
+                .get_prompt(GetPromptRequestParam {
+                    name: prompt.name.to_string(),
+                    arguments: case.arguments.as_object().cloned(),
+                })
+                .await;
+            let outcome = check_prompt_response(response, &case.expect);
+            report.push(ItemKind::Prompt, prompt.name, case.label, outcome);
+        }
+    }
+}
+
+/// Checks a `prompts/get` response against one case's expectation.
+pub fn check_prompt_response(response: Result<GetPromptResult, ServiceError>, expect: &PromptExpectation) -> Outcome {
+    match (expect, response) {
+        (PromptExpectation::Error, Err(_)) => Ok(()),
+        (PromptExpectation::Error, Ok(result)) => {
+            Err(format!("expected an error, got {} messages", result.messages.len()))
+        }
+        (PromptExpectation::Messages(_), Err(e)) => Err(format!("get failed: {e}")),
+        (PromptExpectation::Messages(expected), Ok(result)) => check_prompt_messages(&result, expected),
+    }
+}
+
+fn check_prompt_messages(result: &GetPromptResult, expected: &[ExpectedMessage]) -> Outcome {
+    ensure!(
+        result.messages.len() == expected.len(),
+        "expected {} messages, got {}",
+        expected.len(),
+        result.messages.len()
+    );
+    for (index, (message, expected)) in result.messages.iter().zip(expected).enumerate() {
+        ensure!(
+            message.role == expected.role,
+            "message {index}: expected role {:?}, got {:?}",
+            expected.role,
+            message.role
+        );
+        match (&message.content, &expected.content) {
+            (PromptMessageContent::Text { text }, ExpectedContent::Text(expected)) => {
+                ensure!(text == expected, "message {index}: expected {expected:?}, got {text:?}");
+            }
+            (PromptMessageContent::Resource { resource }, ExpectedContent::EmbeddedText { uri, text }) => {
+                match &resource.resource {
+                    ResourceContents::TextResourceContents { uri: actual_uri, text: actual, .. } => {
+                        ensure!(actual_uri == uri, "message {index}: unexpected embedded uri {actual_uri}");
+                        ensure!(actual == text, "message {index}: expected {text:?}, got {actual:?}");
+                    }
+                    other => return Err(format!("message {index}: expected embedded text, got {other:?}")),
+                }
+            }
+            (other, _) => return Err(format!("message {index}: unexpected content {other:?}")),
+        }
+    }
+    Ok(())
+}
+
+fn single(result: &CallToolResult) -> Result<&Content, String> {
+    match result.content.as_slice() {
+        [content] => Ok(content),
+        contents => Err(format!("expected exactly one content block, got {}", contents.len())),
+    }
+}
+
+fn decode(data: &str) -> Result<Vec<u8>, String> {
+    STANDARD.decode(data).map_err(|e| format!("invalid base64: {e}"))
+}
+
+/// Structural JSON equality that treats numbers by value, so `10` from one SDK
+/// matches `10.0` from another.
+pub fn json_matches(actual: &Value, expected: &Value) -> bool {
+    match (actual, expected) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64() == b.as_f64(),
+        (Value::Array(a), Value::Array(b)) => a.len() == b.len() && a.iter().zip(b).all(|(a, b)| json_matches(a, b)),
+        (Value::Object(a), Value::Object(b)) => {
+            a.len() == b.len() && a.iter().all(|(k, v)| b.get(k).is_some_and(|other| json_matches(v, other)))
+        }
+        _ => actual == expected,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::spec::ExpectedBlock;
+
+    #[test]
+    fn json_matches_compares_numbers_by_value() {
+        assert!(json_matches(&json!(10), &json!(10.0)));
+        assert!(json_matches(&json!({ "sum": 10, "mean": 2.5 }), &json!({ "mean": 2.5, "sum": 10.0 })));
+        assert!(!json_matches(&json!(10), &json!(10.5)));
+        assert!(!json_matches(&json!(10), &json!("10")));
+    }
+
+    #[test]
+    fn json_matches_requires_the_same_shape() {
+        assert!(json_matches(&json!([1, [2, 3]]), &json!([1.0, [2.0, 3.0]])));
+        assert!(!json_matches(&json!([1, 2]), &json!([2, 1])));
+        assert!(!json_matches(&json!([1]), &json!([1, 1])));
+        assert!(!json_matches(&json!({ "a": 1 }), &json!({ "a": 1, "b": 2 })));
+        assert!(!json_matches(&json!({ "a": 1, "b": 2 }), &json!({ "a": 1 })));
+        assert!(!json_matches(&json!({ "a": null }), &json!({ "b": null })));
+    }
+
+    fn invalid_params(data: Option<Value>) -> Result<CallToolResult, ServiceError> {
+        Err(ServiceError::McpError(rmcp::ErrorData::invalid_params("invalid arguments", data)))
+    }
+
+    #[test]
+    fn invalid_params_paths_are_checked_only_when_reported() {
+        let expect = ToolExpectation::InvalidParams { path: "/message" };
+        assert_eq!(check_tool_response(invalid_params(None), &expect), Ok(()));
+        let at_message = json!({ "errors": [{ "path": "/message" }] });
+        assert_eq!(check_tool_response(invalid_params(Some(at_message)), &expect), Ok(()));
+        let elsewhere = json!({ "errors": [{ "path": "/other" }] });
+        assert!(check_tool_response(invalid_params(Some(elsewhere)), &expect).is_err());
+        let internal = Err(ServiceError::McpError(rmcp::ErrorData::internal_error("boom", None)));
+        assert!(check_tool_response(internal, &expect).is_err());
+    }
+
+    #[test]
+    fn blocks_are_checked_in_order() {
+        let text = |text: &str| ExpectedBlock {
+            expect: ToolExpectation::Text(text.to_string()),
+            annotations: None,
+        };
+        let expect = ToolExpectation::Blocks(vec![text("a"), text("b")]);
+        let result = |texts: &[&str]| Ok(CallToolResult::success(texts.iter().map(|t| Content::text(*t)).collect()));
+        assert_eq!(check_tool_response(result(&["a", "b"]), &expect), Ok(()));
+        assert!(check_tool_response(result(&["b", "a"]), &expect).is_err());
+        assert!(check_tool_response(result(&["a"]), &expect).is_err());
+    }
+
+    #[test]
+    fn listing_failures_fail_instead_of_marking_items_missing() {
+        let mut report = ConformanceReport::default();
+        let failed: Result<Vec<()>, _> = Err(ServiceError::McpError(rmcp::ErrorData::internal_error("boom", None)));
+        assert!(listing(failed, ItemKind::Tool, "tools/list", &mut report).is_none());
+        assert_eq!(report.count(crate::Status::Fail), 1);
+        assert_eq!(report.items[0].name, "tools/list");
+        assert!(!report.is_conformant());
+    }
+
+    #[test]
+    fn method_not_found_lists_nothing() {
+        let mut report = ConformanceReport::default();
+        let unsupported: Result<Vec<()>, _> =
+            Err(ServiceError::McpError(rmcp::ErrorData::new(ErrorCode::METHOD_NOT_FOUND, "prompts/list", None)));
+        assert_eq!(listing(unsupported, ItemKind::Prompt, "prompts/list", &mut report), Some(Vec::new()));
+        assert!(report.items.is_empty());
+    }
+}
@@ -0,0 +1,73 @@
+//! Fixture payloads shared by the contract and its checks.
+//!
+//! The Rust e2e server serves these directly; a server in another language
+//! must serve exactly these bytes.
+
+pub const GREETING_URI: &str = "test://static/greeting";
+pub const GREETING_TEXT: &str = "Hello from the MCP Rust test server!";
+
+pub const README_URI: &str = "test://static/readme";
+pub const README_TEXT: &str = "# MCPBench fixture\n\nThis resource is served as markdown.\n";
+
+pub const BYTES_URI: &str = "test://static/bytes";
+
+pub const ECHO_TEMPLATE: &str = "test://echo/{text}";
+pub const PATTERN_TEMPLATE: &str = "test://pattern/{length}";
+
+/// A 2x2 RGB PNG: red, green / blue, white.
+pub const PNG_BASE64: &str =
+    "iVBORw0KGgoAAAANSUhEUgAAAAIAAAACCAIAAAD91JpzAAAAEklEQVR42mP4z8DAAMIM/0EAACboBvroMo0wAAAAAElFTkSuQmCC";
+
+/// Every byte value exactly once, in order.
+pub fn fixture_bytes() -> Vec<u8> {
+    (0..=255u8).collect()
+}
+
+/// `length` bytes where byte `i` is `i % 256`.
+pub fn pattern_bytes(length: usize) -> Vec<u8> {
+    (0..length).map(|i| (i % 256) as u8).collect()
+}
+
+/// 8 kHz mono 8-bit PCM WAV holding a square wave with an 8-sample half period.
+pub fn wav_bytes() -> Vec<u8> {
+    let samples: Vec<u8> = (0..64).map(|i| if (i / 8) % 2 == 0 { 0xC0 } else { 0x40 }).collect();
+    let data_len = samples.len() as u32;
+
+    let mut wav = Vec::with_capacity(44 + samples.len());
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+    wav.extend_from_slice(&8000u32.to_le_bytes());
+    wav.extend_from_slice(&8000u32.to_le_bytes()); // byte rate
+    wav.extend_from_slice(&1u16.to_le_bytes()); // block align
+    wav.extend_from_slice(&8u16.to_le_bytes()); // bits per sample
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+    wav.extend_from_slice(&samples);
+    wav
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wav_header_sizes_match_the_data() {
+        let wav = wav_bytes();
+        let riff_len = u32::from_le_bytes(wav[4..8].try_into().unwrap()) as usize;
+        let data_len = u32::from_le_bytes(wav[40..44].try_into().unwrap()) as usize;
+        assert_eq!(riff_len, wav.len() - 8);
+        assert_eq!(data_len, wav.len() - 44);
+        assert_eq!(&wav[36..40], b"data");
+    }
+
+    #[test]
+    fn pattern_bytes_wrap_every_256() {
+        let bytes = pattern_bytes(300);
+        assert_eq!(bytes[..256], fixture_bytes()[..]);
+        assert_eq!(bytes[256..], fixture_bytes()[..44]);
+    }
+}
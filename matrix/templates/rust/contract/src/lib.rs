@@ -0,0 +1,18 @@
+//! Canonical fixture contract for MCPBench e2e servers.
+//!
+//! Every server template in the matrix is expected to expose the same fixture
+//! tools, resources and prompts with the same responses. This crate states that
+//! contract as data ([`canonical`]) and provides a checker ([`check`]) that runs
+//! it against a connected server and returns a per-item [`ConformanceReport`].
+
+pub mod canonical;
+pub mod check;
+pub mod fixtures;
+pub mod logging;
+pub mod report;
+pub mod spec;
+
+pub use canonical::contract;
+pub use check::check;
+pub use report::{ConformanceReport, ItemKind, ItemResult, Status};
+pub use spec::Contract;
@@ -0,0 +1,26 @@
+//! MCP logging levels in severity order, as used by `logging/setLevel`.
+//!
+//! A server sends a `notifications/message` only when its level is at least
+//! the one the client set; both sides compare levels with [`severity`].
+
+use rmcp::model::LoggingLevel;
+
+/// All MCP logging levels, from least to most severe.
+pub const LOG_LEVELS: [LoggingLevel; 8] = [
+    LoggingLevel::Debug,
+    LoggingLevel::Info,
+    LoggingLevel::Notice,
+    LoggingLevel::Warning,
+    LoggingLevel::Error,
+    LoggingLevel::Critical,
+    LoggingLevel::Alert,
+    LoggingLevel::Emergency,
+];
+
+/// Position of `level` in [`LOG_LEVELS`]; higher is more severe.
+pub fn severity(level: LoggingLevel) -> usize {
+    LOG_LEVELS
+        .iter()
+        .position(|l| *l == level)
+        .expect("LOG_LEVELS covers every level")
+}
@@ -0,0 +1,78 @@
+//! Per-item conformance results.
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ItemKind {
+    Tool,
+    Resource,
+    Prompt,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Pass,
+    Fail,
+    /// The server does not list the item at all.
+    Missing,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ItemResult {
+    pub kind: ItemKind,
+    /// Tool or prompt name, or resource URI.
+    pub name: String,
+    /// Which case of the item was checked; empty for resources.
+    pub case: String,
+    pub status: Status,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ConformanceReport {
+    pub items: Vec<ItemResult>,
+}
+
+impl ConformanceReport {
+    pub fn count(&self, status: Status) -> usize {
+        self.items.iter().filter(|item| item.status == status).count()
+    }
+
+    /// True when every item passed.
+    pub fn is_conformant(&self) -> bool {
+        self.items.iter().all(|item| item.status == Status::Pass)
+    }
+
+    pub(crate) fn push(
+        &mut self,
+        kind: ItemKind,
+        name: impl Into<String>,
+        case: impl Into<String>,
+        outcome: Result<(), String>,
+    ) {
+        let (status, detail) = match outcome {
+            Ok(()) => (Status::Pass, None),
+            Err(detail) => (Status::Fail, Some(detail)),
+        };
+        self.items.push(ItemResult {
+            kind,
+            name: name.into(),
+            case: case.into(),
+            status,
+            detail,
+        });
+    }
+
+    pub(crate) fn push_missing(&mut self, kind: ItemKind, name: impl Into<String>, case: impl Into<String>) {
+        self.items.push(ItemResult {
+            kind,
+            name: name.into(),
+            case: case.into(),
+            status: Status::Missing,
+            detail: None,
+        });
+    }
+}
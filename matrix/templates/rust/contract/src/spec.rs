@@ -0,0 +1,142 @@
+//! Types describing the fixture contract.
+
+use rmcp::model::{PromptMessageRole, Role};
+use serde_json::Value;
+
+#[derive(Debug, Clone, Default)]
+pub struct Contract {
+    pub tools: Vec<ToolContract>,
+    pub resources: Vec<ResourceContract>,
+    pub prompts: Vec<PromptContract>,
+}
+
+/// A tool that must be listed, and the calls it must answer.
+#[derive(Debug, Clone)]
+pub struct ToolContract {
+    pub name: &'static str,
+    pub cases: Vec<ToolCase>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ToolCase {
+    /// Short label shown in the report, e.g. `"missing message"`.
+    pub label: &'static str,
+    pub arguments: Value,
+    pub expect: ToolExpectation,
+}
+
+#[derive(Debug, Clone)]
+pub enum ToolExpectation {
+    /// A single text block with exactly this text.
+    Text(String),
+    /// A single text block starting with this text.
+    TextPrefix(String),
+    /// `structuredContent` equal to this value, comparing numbers by value.
+    Structured(Value),
+    Image { mime_type: &'static str, bytes: Vec<u8> },
+    Audio { mime_type: &'static str, bytes: Vec<u8> },
+    EmbeddedText { uri: &'static str, mime_type: &'static str, text: String },
+    EmbeddedBlob { uri: &'static str, mime_type: &'static str, bytes: Vec<u8> },
+    ResourceLink { uri: &'static str, mime_type: &'static str },
+    /// Several content blocks in this order, each matching a single-block expectation.
+    Blocks(Vec<ExpectedBlock>),
+    /// A JSON-RPC `invalid_params` error. Servers that list the failing
+    /// arguments in `data.errors[].path` must include this pointer; the
+    /// `data` shape is not part of the protocol, so its absence is accepted.
+    InvalidParams { path: &'static str },
+}
+
+/// One block of a [`ToolExpectation::Blocks`] result.
+#[derive(Debug, Clone)]
+pub struct ExpectedBlock {
+    pub expect: ToolExpectation,
+    pub annotations: Option<ExpectedAnnotations>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExpectedAnnotations {
+    pub audience: Vec<Role>,
+    pub priority: f32,
+}
+
+impl ToolExpectation {
+    /// The first protocol revision that defines what this expectation checks,
+    /// or `None` if every revision does.
+    pub fn since(&self) -> Option<&'static str> {
+        match self {
+            ToolExpectation::Audio { .. } => Some("2025-03-26"),
+            ToolExpectation::Structured(_) | ToolExpectation::ResourceLink { .. } => Some("2025-06-18"),
+            // Dates sort lexically, so the newest block decides
+            ToolExpectation::Blocks(blocks) => blocks.iter().filter_map(|block| block.expect.since()).max(),
+            _ => None,
+        }
+    }
+}
+
+impl Contract {
+    /// This contract without the tool cases `protocol_version` does not define,
+    /// dropping tools left with no cases.
+    pub fn for_version(mut self, protocol_version: &str) -> Self {
+        // Dates sort lexically, so this is a version comparison
+        for tool in &mut self.tools {
+            tool.cases
+                .retain(|case| case.expect.since().is_none_or(|since| since <= protocol_version));
+        }
+        self.tools.retain(|tool| !tool.cases.is_empty());
+        self
+    }
+}
+
+/// Where a resource is expected to be discoverable.
+#[derive(Debug, Clone)]
+pub enum ResourceSource {
+    /// Listed by `resources/list` under the same URI.
+    Static,
+    /// Produced by the `resources/templates/list` entry with this URI template.
+    Template(&'static str),
+}
+
+#[derive(Debug, Clone)]
+pub struct ResourceContract {
+    pub uri: String,
+    pub source: ResourceSource,
+    pub expect: ResourceExpectation,
+}
+
+#[derive(Debug, Clone)]
+pub enum ResourceExpectation {
+    Text { mime_type: &'static str, text: String },
+    Blob { mime_type: &'static str, bytes: Vec<u8> },
+}
+
+#[derive(Debug, Clone)]
+pub struct PromptContract {
+    pub name: &'static str,
+    pub cases: Vec<PromptCase>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PromptCase {
+    pub label: &'static str,
+    pub arguments: Value,
+    pub expect: PromptExpectation,
+}
+
+#[derive(Debug, Clone)]
+pub enum PromptExpectation {
+    Messages(Vec<ExpectedMessage>),
+    /// The request must fail, e.g. because a required argument is missing.
+    Error,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExpectedMessage {
+    pub role: PromptMessageRole,
+    pub content: ExpectedContent,
+}
+
+#[derive(Debug, Clone)]
+pub enum ExpectedContent {
+    Text(String),
+    EmbeddedText { uri: &'static str, text: String },
+}
@@ -0,0 +1,61 @@
+//! Fixture payloads for the content-type tools (`get_image`, `get_audio`, ...).
+//!
+//! Clients decode these and compare bytes against `mcpbench_contract::fixtures`,
+//! which is where the payloads themselves live.
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use mcpbench_contract::fixtures::{wav_bytes, PNG_BASE64};
+use rmcp::model::{AnnotateAble, Content, RawAudioContent, RawContent, RawEmbeddedResource, Role};
+
+use crate::resources;
+
+pub fn image() -> Content {
+    Content::image(PNG_BASE64, "image/png")
+}
+
+pub fn audio() -> Content {
+    RawContent::Audio(RawAudioContent {
+        data: STANDARD.encode(wav_bytes()),
+        mime_type: "audio/wav".to_string(),
+    })
+    .no_annotation()
+}
+
+pub fn embedded_text() -> Content {
+    embedded(resources::GREETING_URI)
+}
+
+pub fn embedded_blob() -> Content {
+    embedded(resources::BYTES_URI)
+}
+
+pub fn resource_link() -> Content {
+    let readme = resources::list()
+        .into_iter()
+        .find(|r| r.uri == resources::README_URI)
+        .expect("readme fixture is static");
+    RawContent::ResourceLink(readme.raw).no_annotation()
+}
+
+/// One block of every kind, with annotations on the text and image blocks.
+pub fn mixed() -> Vec<Content> {
+    vec![
+        RawContent::text("Mixed content response")
+            .with_audience(vec![Role::User])
+            .with_priority(1.0),
+        RawContent::image(PNG_BASE64, "image/png")
+            .with_audience(vec![Role::Assistant])
+            .with_priority(0.5),
+        audio(),
+        embedded_text(),
+        resource_link(),
+    ]
+}
+
+fn embedded(uri: &str) -> Content {
+    let contents = resources::read(uri).expect("embedded fixtures are static");
+    RawContent::Resource(RawEmbeddedResource {
+        meta: None,
+        resource: contents,
+    }).no_annotation()
+}
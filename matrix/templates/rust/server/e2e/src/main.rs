@@ -1,125 +1,906 @@
-use std::sync::Arc;
+mod content;
+mod pagination;
+mod prompts;
+mod registry;
+mod resources;
+mod tools;
+mod version;
+
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    sync::{
+        atomic::{AtomicI64, AtomicUsize, Ordering},
+        Arc,
+    },
+};
 use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+use pagination::ToolPagination;
+use mcpbench_contract::logging::{severity, LOG_LEVELS};
+use registry::{InputValidator, ToolRegistry};
+use version::ProtocolVersions;
 use rmcp::{
-    model::{CallToolResult, Content, CallToolRequestParam, ListToolsResult, ServerInfo, ProtocolVersion, ServerCapabilities},
-    service::ServiceExt,
-    transport::streamable_http_server::StreamableHttpService,
-    Error as McpError,
+    model::{
+        CallToolResult, Content, CallToolRequestParam, ListToolsResult, ServerInfo, ProtocolVersion, ServerCapabilities,
+        ListResourcesResult, ListResourceTemplatesResult, ReadResourceRequestParam, ReadResourceResult,
+        ListPromptsResult, GetPromptRequestParam, GetPromptResult,
+        CreateMessageRequestParam, SamplingMessage, Role, ContextInclusion,
+        CreateElicitationRequestParam, ElicitationAction,
+        ProgressNotificationParam, ProgressToken,
+        LoggingLevel, LoggingMessageNotificationParam, SetLevelRequestParam,
+        InitializeRequestParam, InitializeResult, Implementation,
+    },
+    service::{NotificationContext, Peer, RoleServer, ServiceExt},
+    transport::{
+        sse_server::{SseServer, SseServerConfig},
+        streamable_http_server::{
+            session::local::LocalSessionManager, StreamableHttpServerConfig, StreamableHttpService,
+        },
+    },
+    ErrorData as McpError,
     ServerHandler,
-    tool,
 };
 
+/// Fixture server for one session. Every transport serves each session from
+/// its own [`TestServer::new_session`], so [`SessionState`] never leaks
+/// between sessions while [`SharedState`] is seen by all of them.
 #[derive(Clone)]
 struct TestServer {
-    message: Arc<Mutex<String>>,
+    session: Arc<SessionState>,
+    shared: Arc<SharedState>,
+}
+
+/// State belonging to a single session.
+struct SessionState {
+    message: Mutex<String>,
+    roots_list_changed: AtomicUsize,
+    log_level: Mutex<LoggingLevel>,
+    counter: AtomicI64,
+    operations: Mutex<Operations>,
+}
+
+impl Default for SessionState {
+    fn default() -> Self {
+        Self {
+            message: Mutex::new("No message".to_string()),
+            roots_list_changed: AtomicUsize::new(0),
+            log_level: Mutex::new(LoggingLevel::Info),
+            counter: AtomicI64::new(0),
+            operations: Mutex::new(Operations::default()),
+        }
+    }
+}
+
+/// State shared by every session of the server process.
+struct SharedState {
+    pagination: ToolPagination,
+    dynamic_tools: Mutex<BTreeMap<String, String>>,
+    peers: Mutex<Peers>,
+    tools: ToolRegistry,
+    /// Dynamic and generated tools all take [`empty_input_schema`], so they
+    /// share one validator compiled at startup.
+    runtime_tools: InputValidator,
+    protocol_versions: ProtocolVersions,
+}
+
+/// Prefix required for tools registered at runtime through `add_tool`.
+const DYNAMIC_TOOL_PREFIX: &str = "dynamic_";
+
+/// Lifecycle of a `slow_operation` call, reported by `get_operation_status`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OperationStatus {
+    Running,
+    Completed,
+    Cancelled,
+}
+
+impl OperationStatus {
+    fn as_str(&self) -> &'static str {
+        match self {
+            OperationStatus::Running => "running",
+            OperationStatus::Completed => "completed",
+            OperationStatus::Cancelled => "cancelled",
+        }
+    }
+}
+
+/// Initialized sessions, notified of tool list changes. Each gets an id so an
+/// ended one can be removed without touching sessions that registered in the
+/// meantime.
+#[derive(Default)]
+struct Peers {
+    next_id: u64,
+    peers: BTreeMap<u64, Peer<RoleServer>>,
+}
+
+impl Peers {
+    fn register(&mut self, peer: Peer<RoleServer>) -> u64 {
+        let id = self.next_id;
+        self.peers.insert(id, peer);
+        self.next_id += 1;
+        id
+    }
+}
+
+/// How often to check whether a session's transport has closed; rmcp does not
+/// signal the end of a session to the handler.
+const SESSION_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(200);
+
+/// Finished operations remembered per session for `get_operation_status`; older ones are forgotten.
+const MAX_FINISHED_OPERATIONS: usize = 256;
+
+/// `slow_operation` statuses by operation id. Running operations are always
+/// kept; finished ones only until [`MAX_FINISHED_OPERATIONS`] newer ones exist.
+#[derive(Default)]
+struct Operations {
+    statuses: HashMap<String, OperationStatus>,
+    finished: VecDeque<String>,
+}
+
+impl Operations {
+    fn start(&mut self, operation_id: String) {
+        self.statuses.insert(operation_id, OperationStatus::Running);
+    }
+
+    fn finish(&mut self, operation_id: String, status: OperationStatus) {
+        self.statuses.insert(operation_id.clone(), status);
+        self.finished.push_back(operation_id);
+        while self.finished.len() > MAX_FINISHED_OPERATIONS {
+            let oldest = self.finished.pop_front().unwrap();
+            // The id may have been reused by a call that is running or finished more recently
+            let reused = self.finished.contains(&oldest)
+                || self.statuses.get(&oldest) == Some(&OperationStatus::Running);
+            if !reused {
+                self.statuses.remove(&oldest);
+            }
+        }
+    }
+
+    fn status(&self, operation_id: &str) -> Option<OperationStatus> {
+        self.statuses.get(operation_id).copied()
+    }
 }
 
 impl TestServer {
-    fn new() -> Self {
+    fn new(pagination: ToolPagination, protocol_versions: ProtocolVersions) -> Self {
+        Self {
+            session: Arc::default(),
+            shared: Arc::new(SharedState {
+                pagination,
+                dynamic_tools: Mutex::new(BTreeMap::new()),
+                peers: Mutex::new(Peers::default()),
+                tools: tools::registry(),
+                runtime_tools: InputValidator::new(&empty_input_schema()),
+                protocol_versions,
+            }),
+        }
+    }
+
+    /// A server for a new session: fresh session state, the same shared state.
+    fn new_session(&self) -> Self {
         Self {
-            message: Arc::new(Mutex::new("No message".to_string())),
+            session: Arc::default(),
+            shared: self.shared.clone(),
+        }
+    }
+
+    /// The version agreed with `peer`, or the latest supported one before
+    /// `initialize`. Negotiation is deterministic, so it is recomputed from the
+    /// client's request instead of being stored where other sessions could
+    /// overwrite it.
+    fn negotiated_version(&self, peer: &Peer<RoleServer>) -> ProtocolVersion {
+        match peer.peer_info() {
+            Some(client) => self.shared.protocol_versions.negotiate(&client.protocol_version),
+            None => self.shared.protocol_versions.latest(),
         }
     }
 
+    async fn get_server_info(&self, peer: &Peer<RoleServer>) -> Result<CallToolResult, McpError> {
+        let info = ServerInfo {
+            protocol_version: self.negotiated_version(peer),
+            ..self.get_info()
+        };
+        let server_info = serde_json::json!({
+            "name": info.server_info.name,
+            "version": info.server_info.version,
+            "language": "rust",
+            "sdk": "rmcp",
+            "protocolVersion": info.protocol_version,
+            "capabilities": info.capabilities,
+            "tools": self.shared.tools.tools().map(|t| t.name).collect::<Vec<_>>(),
+        });
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Server Information: {}",
+            serde_json::to_string_pretty(&server_info).unwrap_or_default()
+        ))]))
+    }
+
+    /// Adds `value` to this session's counter, so repeated calls accumulate
+    /// within a session but never leak into another one.
+    async fn increment(&self, value: i64) -> Result<CallToolResult, McpError> {
+        let previous = self
+            .session
+            .counter
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |counter| counter.checked_add(value))
+            .map_err(|counter| {
+                McpError::invalid_params(
+                    format!("Incrementing {} by {} would overflow the counter", counter, value),
+                    Some(serde_json::json!({ "counter": counter, "value": value })),
+                )
+            })?;
+        let counter = previous + value;
+        Ok(CallToolResult::success(vec![Content::text(
+            format!("Counter incremented by {}. New value: {}", value, counter)
+        )]))
+    }
+
     async fn handle_message(&self, message: String) -> Result<CallToolResult, McpError> {
-        let mut current = self.message.lock().await;
+        let mut current = self.session.message.lock().await;
         *current = message;
         Ok(CallToolResult::success(vec![Content::text(
             format!("Received message: {}", current)
         )]))
     }
-}
 
-#[tool(tool_box)]
-impl TestServer {
-    #[tool(description = "Send a message to the server")]
-    async fn send_message(&self, message: String) -> Result<CallToolResult, McpError> {
-        self.handle_message(message).await
+    async fn sample_llm(
+        &self,
+        peer: &Peer<RoleServer>,
+        prompt: String,
+        max_tokens: u32,
+    ) -> Result<CallToolResult, McpError> {
+        let supports_sampling = peer
+            .peer_info()
+            .is_some_and(|info| info.capabilities.sampling.is_some());
+        if !supports_sampling {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "Client does not support sampling",
+            )]));
+        }
+
+        let result = peer
+            .create_message(CreateMessageRequestParam {
+                messages: vec![SamplingMessage {
+                    role: Role::User,
+                    content: Content::text(prompt),
+                }],
+                model_preferences: None,
+                system_prompt: Some("You are a deterministic test model.".to_string()),
+                include_context: Some(ContextInclusion::None),
+                temperature: Some(0.0),
+                max_tokens,
+                stop_sequences: None,
+                metadata: None,
+            })
+            .await
+            .map_err(|e| McpError::internal_error(format!("Sampling request failed: {}", e), None))?;
+
+        let text = result
+            .message
+            .content
+            .as_text()
+            .map(|t| t.text.clone())
+            .ok_or_else(|| McpError::internal_error("Sampling result is not text", None))?;
+        Ok(CallToolResult::success(vec![Content::text(
+            format!("LLM sampling result: {}", text)
+        )]))
     }
-}
 
-impl ServerHandler for TestServer {
-    fn call_tool(
+    async fn list_roots(&self, peer: &Peer<RoleServer>) -> Result<CallToolResult, McpError> {
+        let supports_roots = peer
+            .peer_info()
+            .is_some_and(|info| info.capabilities.roots.is_some());
+        if !supports_roots {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "Client does not support roots",
+            )]));
+        }
+
+        let result = peer
+            .list_roots()
+            .await
+            .map_err(|e| McpError::internal_error(format!("roots/list request failed: {}", e), None))?;
+
+        // Report the notification count alongside the roots so the client can
+        // check that its roots/list_changed notification reached this session.
+        Ok(CallToolResult::success(vec![Content::text(
+            serde_json::json!({
+                "roots": result.roots,
+                "list_changed_notifications": self.session.roots_list_changed.load(Ordering::SeqCst),
+            })
+            .to_string(),
+        )]))
+    }
+
+    async fn elicit_user_info(&self, peer: &Peer<RoleServer>) -> Result<CallToolResult, McpError> {
+        let supports_elicitation = peer
+            .peer_info()
+            .is_some_and(|info| info.capabilities.elicitation.is_some());
+        if !supports_elicitation {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "Client does not support elicitation",
+            )]));
+        }
+
+        let result = peer
+            .create_elicitation(CreateElicitationRequestParam {
+                message: "Please provide your user information".to_string(),
+                requested_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "name": { "type": "string", "description": "Your name" },
+                        "age": { "type": "integer", "minimum": 0, "description": "Your age" },
+                        "subscribe": { "type": "boolean", "description": "Subscribe to updates" }
+                    },
+                    "required": ["name"]
+                }).as_object().unwrap().clone(),
+            })
+            .await
+            .map_err(|e| McpError::internal_error(format!("Elicitation request failed: {}", e), None))?;
+
+        let text = match result.action {
+            ElicitationAction::Accept => {
+                let content = result.content.unwrap_or_default();
+                if !content.get("name").is_some_and(|v| v.is_string()) {
+                    return Ok(CallToolResult::error(vec![Content::text(
+                        format!("Elicitation accepted without required field name: {}", content)
+                    )]));
+                }
+                format!("Elicitation accepted: {}", content)
+            }
+            ElicitationAction::Decline => "Elicitation declined".to_string(),
+            ElicitationAction::Cancel => "Elicitation cancelled".to_string(),
+        };
+        Ok(CallToolResult::success(vec![Content::text(text)]))
+    }
+
+    async fn long_running_operation(
+        &self,
+        peer: &Peer<RoleServer>,
+        progress_token: Option<ProgressToken>,
+        steps: u32,
+        duration_ms: u64,
+    ) -> Result<CallToolResult, McpError> {
+        let step_delay = std::time::Duration::from_millis(duration_ms / steps.max(1) as u64);
+        for step in 1..=steps {
+            tokio::time::sleep(step_delay).await;
+            if let Some(progress_token) = &progress_token {
+                peer.notify_progress(ProgressNotificationParam {
+                    progress_token: progress_token.clone(),
+                    progress: step as f64,
+                    total: Some(steps as f64),
+                    message: Some(format!("Step {}/{}", step, steps)),
+                })
+                .await
+                .map_err(|e| McpError::internal_error(format!("Failed to send progress: {}", e), None))?;
+            }
+        }
+        Ok(CallToolResult::success(vec![Content::text(
+            format!("Long running operation completed: {} steps", steps)
+        )]))
+    }
+
+    async fn slow_operation(
+        &self,
+        ct: &tokio_util::sync::CancellationToken,
+        peer: &Peer<RoleServer>,
+        operation_id: String,
+        duration_ms: u64,
+    ) -> Result<CallToolResult, McpError> {
+        self.session.operations.lock().await.start(operation_id.clone());
+
+        let status = tokio::select! {
+            _ = tokio::time::sleep(std::time::Duration::from_millis(duration_ms)) => OperationStatus::Completed,
+            _ = ct.cancelled() => OperationStatus::Cancelled,
+        };
+        self.session.operations.lock().await.finish(operation_id.clone(), status);
+
+        match status {
+            OperationStatus::Cancelled => {
+                log::info!("Operation {} cancelled by client", operation_id);
+                // rmcp sends whatever the handler returns, but a cancelled request must get
+                // no response at all, so hold on to it until the session has gone away
+                while !peer.is_transport_closed() {
+                    tokio::time::sleep(SESSION_POLL_INTERVAL).await;
+                }
+                Err(McpError::internal_error(
+                    format!("Operation {} was cancelled", operation_id),
+                    None,
+                ))
+            }
+            _ => Ok(CallToolResult::success(vec![Content::text(
+                format!("Slow operation {} completed", operation_id)
+            )])),
+        }
+    }
+
+    async fn get_operation_status(&self, operation_id: String) -> Result<CallToolResult, McpError> {
+        let status = self
+            .session
+            .operations
+            .lock()
+            .await
+            .status(&operation_id)
+            .as_ref()
+            .map(OperationStatus::as_str)
+            .unwrap_or("unknown");
+        Ok(CallToolResult::success(vec![Content::text(status)]))
+    }
+
+    async fn emit_log_messages(&self, peer: &Peer<RoleServer>) -> Result<CallToolResult, McpError> {
+        let threshold = *self.session.log_level.lock().await;
+        let mut emitted = 0;
+        for level in LOG_LEVELS {
+            if severity(level) < severity(threshold) {
+                continue;
+            }
+            peer.notify_logging_message(LoggingMessageNotificationParam {
+                level,
+                logger: Some("test_server".to_string()),
+                data: serde_json::json!({
+                    "message": format!("Test log message at {:?} level", level),
+                }),
+            })
+            .await
+            .map_err(|e| McpError::internal_error(format!("Failed to send log message: {}", e), None))?;
+            emitted += 1;
+        }
+        Ok(CallToolResult::success(vec![Content::text(
+            format!("Emitted {} log messages", emitted)
+        )]))
+    }
+
+    async fn add_tool(&self, name: String, description: String) -> Result<CallToolResult, McpError> {
+        if !name.starts_with(DYNAMIC_TOOL_PREFIX) || name.len() == DYNAMIC_TOOL_PREFIX.len() {
+            return Err(McpError::invalid_params(
+                format!("Dynamic tool names must start with {:?}", DYNAMIC_TOOL_PREFIX),
+                Some(serde_json::json!({ "name": name })),
+            ));
+        }
+        let added = self
+            .shared
+            .dynamic_tools
+            .lock()
+            .await
+            .insert(name.clone(), description)
+            .is_none();
+        // A changed description changes the listing too
+        self.notify_tool_list_changed().await;
+        Ok(CallToolResult::success(vec![Content::text(if added {
+            format!("Tool {} added", name)
+        } else {
+            format!("Tool {} updated", name)
+        })]))
+    }
+
+    async fn remove_tool(&self, name: String) -> Result<CallToolResult, McpError> {
+        if self.shared.dynamic_tools.lock().await.remove(&name).is_none() {
+            return Err(McpError::invalid_params(
+                format!("No dynamic tool named {}", name),
+                Some(serde_json::json!({ "name": name })),
+            ));
+        }
+        self.notify_tool_list_changed().await;
+        Ok(CallToolResult::success(vec![Content::text(
+            format!("Tool {} removed", name)
+        )]))
+    }
+
+    /// Sends `notifications/tools/list_changed` to every connected session,
+    /// forgetting sessions whose transport has gone away.
+    async fn notify_tool_list_changed(&self) {
+        let peers: Vec<_> = self
+            .shared
+            .peers
+            .lock()
+            .await
+            .peers
+            .iter()
+            .map(|(id, peer)| (*id, peer.clone()))
+            .collect();
+        let mut disconnected = Vec::new();
+        for (id, peer) in &peers {
+            if let Err(e) = peer.notify_tool_list_changed().await {
+                log::info!("Dropping disconnected session: {}", e);
+                disconnected.push(*id);
+            }
+        }
+        log::info!("Sent tools/list_changed to {} session(s)", peers.len() - disconnected.len());
+        self.shared
+            .peers
+            .lock()
+            .await
+            .peers
+            .retain(|id, _| !disconnected.contains(id));
+    }
+
+    async fn dynamic_tools(&self) -> Vec<rmcp::model::Tool> {
+        self.shared
+            .dynamic_tools
+            .lock()
+            .await
+            .iter()
+            .map(|(name, description)| dynamic_tool(name, description))
+            .collect()
+    }
+
+    async fn get_weather(&self, city: String) -> Result<CallToolResult, McpError> {
+        Ok(CallToolResult::structured(serde_json::json!({
+            "city": city,
+            "temperature": 22.5,
+            "conditions": "Sunny",
+            "humidity": 65
+        })))
+    }
+
+    async fn calculate_stats(&self, numbers: Vec<f64>) -> Result<CallToolResult, McpError> {
+        if numbers.is_empty() {
+            return Err(McpError::invalid_params(
+                "numbers must not be empty",
+                Some(serde_json::json!({ "argument": "numbers" })),
+            ));
+        }
+        let sum: f64 = numbers.iter().sum();
+        Ok(CallToolResult::structured(serde_json::json!({
+            "count": numbers.len(),
+            "sum": sum,
+            "mean": sum / numbers.len() as f64,
+            "min": numbers.iter().copied().fold(f64::INFINITY, f64::min),
+            "max": numbers.iter().copied().fold(f64::NEG_INFINITY, f64::max)
+        })))
+    }
+
+    /// Runs a registry, dynamic or generated tool, with its arguments
+    /// validated against the tool's input schema.
+    async fn dispatch_tool(
         &self,
         request: CallToolRequestParam,
-        _context: rmcp::service::RequestContext<rmcp::service::RoleServer>,
-    ) -> impl std::future::Future<Output = Result<CallToolResult, McpError>> + Send + '_ {
-        async move {
-            match request.name.as_str() {
-                "send_message" => {
-                    let message = request.arguments
-                        .and_then(|args| args.get("message"))
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("No message")
-                        .to_string();
-                    self.handle_message(message).await
+        context: rmcp::service::RequestContext<rmcp::service::RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        if let Some(tool) = self.shared.tools.get(&request.name) {
+            return tool.call(self.clone(), request.arguments, context).await;
+        }
+
+        // Runtime tools are not in the registry but get the same argument checks
+        let arguments = request.arguments.unwrap_or_default();
+        match request.name.as_ref() {
+            name if name.starts_with(DYNAMIC_TOOL_PREFIX) => {
+                if !self.shared.dynamic_tools.lock().await.contains_key(name) {
+                    return Err(McpError::method_not_found::<rmcp::model::CallToolRequestMethod>());
                 }
-                _ => Err(McpError::method_not_found::<rmcp::model::CallToolRequestMethod>()),
+                self.shared.runtime_tools.validate(name, &arguments)?;
+                Ok(CallToolResult::success(vec![Content::text(
+                    format!("Dynamic tool {} called", name)
+                )]))
             }
+            name => match self.generated_tool_index(name) {
+                Some(_) => {
+                    self.shared.runtime_tools.validate(name, &arguments)?;
+                    Ok(CallToolResult::success(vec![Content::text(
+                        format!("Generated tool {} called", name)
+                    )]))
+                }
+                None => Err(McpError::method_not_found::<rmcp::model::CallToolRequestMethod>()),
+            },
+        }
+    }
+
+    fn generated_tool_index(&self, name: &str) -> Option<usize> {
+        let index = name.strip_prefix("generated_tool_")?.parse::<usize>().ok()?;
+        // Only the listed spelling names a tool, not "+5" or "5" for "005"
+        (index < self.shared.pagination.generated_tools && generated_tool_name(index) == name).then_some(index)
+    }
+
+    fn generated_tools(&self) -> impl Iterator<Item = rmcp::model::Tool> {
+        (0..self.shared.pagination.generated_tools).map(generated_tool)
+    }
+}
+
+fn dynamic_tool(name: &str, description: &str) -> rmcp::model::Tool {
+    rmcp::model::Tool::new(name.to_string(), description.to_string(), empty_input_schema())
+}
+
+fn generated_tool_name(index: usize) -> String {
+    format!("generated_tool_{:03}", index)
+}
+
+fn generated_tool(index: usize) -> rmcp::model::Tool {
+    rmcp::model::Tool::new(
+        generated_tool_name(index),
+        format!("Generated tool #{} for pagination tests", index),
+        empty_input_schema(),
+    )
+}
+
+/// Input schema for tools that take no arguments.
+fn empty_input_schema() -> Arc<rmcp::model::JsonObject> {
+    Arc::new(serde_json::json!({
+        "type": "object",
+        "properties": {}
+    }).as_object().unwrap().clone())
+}
+
+impl ServerHandler for TestServer {
+    async fn initialize(
+        &self,
+        request: InitializeRequestParam,
+        context: rmcp::service::RequestContext<rmcp::service::RoleServer>,
+    ) -> Result<InitializeResult, McpError> {
+        let negotiated = self.shared.protocol_versions.negotiate(&request.protocol_version);
+        if negotiated != request.protocol_version {
+            log::info!(
+                "Client requested protocol version {}, counter-offering {}",
+                version::as_str(&request.protocol_version),
+                version::as_str(&negotiated)
+            );
+        } else {
+            log::info!("Negotiated protocol version {}", version::as_str(&negotiated));
         }
+
+        if context.peer.peer_info().is_none() {
+            context.peer.set_peer_info(request);
+        }
+        Ok(ServerInfo {
+            protocol_version: negotiated,
+            ..self.get_info()
+        })
     }
 
-    fn list_tools(
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+        context: rmcp::service::RequestContext<rmcp::service::RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let negotiated = self.negotiated_version(&context.peer);
+        let result = self.dispatch_tool(request, context).await?;
+        Ok(version::gate_result(result, &negotiated))
+    }
+
+    async fn list_tools(
+        &self,
+        request: Option<rmcp::model::PaginatedRequestParam>,
+        context: rmcp::service::RequestContext<rmcp::service::RoleServer>,
+    ) -> Result<ListToolsResult, McpError> {
+        let mut tools: Vec<_> = self.shared.tools.tools().collect();
+        tools.extend(self.dynamic_tools().await);
+        tools.extend(self.generated_tools());
+        let negotiated = self.negotiated_version(&context.peer);
+        let tools = tools
+            .into_iter()
+            .map(|tool| version::gate_tool(tool, &negotiated))
+            .collect();
+
+        let cursor = request.as_ref().and_then(|r| r.cursor.as_deref());
+        let (tools, next_cursor) = pagination::paginate(tools, cursor, self.shared.pagination.page_size)?;
+        Ok(ListToolsResult { tools, next_cursor })
+    }
+
+    async fn list_resources(
         &self,
         _request: Option<rmcp::model::PaginatedRequestParam>,
         _context: rmcp::service::RequestContext<rmcp::service::RoleServer>,
-    ) -> impl std::future::Future<Output = Result<ListToolsResult, McpError>> + Send + '_ {
-        async move {
-            Ok(ListToolsResult {
-                tools: vec![rmcp::model::Tool {
-                    name: "send_message".into(),
-                    description: Some("Send a message to the server".into()),
-                    input_schema: Arc::new(serde_json::json!({
-                        "type": "object",
-                        "properties": {
-                            "message": {
-                                "type": "string",
-                                "description": "Message to send"
-                            }
-                        },
-                        "required": ["message"]
-                    }).as_object().unwrap().clone()),
-                    annotations: None,
-                }],
-                next_cursor: None,
-            })
+    ) -> Result<ListResourcesResult, McpError> {
+        Ok(ListResourcesResult {
+            resources: resources::list(),
+            next_cursor: None,
+        })
+    }
+
+    async fn list_resource_templates(
+        &self,
+        _request: Option<rmcp::model::PaginatedRequestParam>,
+        _context: rmcp::service::RequestContext<rmcp::service::RoleServer>,
+    ) -> Result<ListResourceTemplatesResult, McpError> {
+        Ok(ListResourceTemplatesResult {
+            resource_templates: resources::templates(),
+            next_cursor: None,
+        })
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParam,
+        _context: rmcp::service::RequestContext<rmcp::service::RoleServer>,
+    ) -> Result<ReadResourceResult, McpError> {
+        match resources::read(&request.uri) {
+            Some(contents) => Ok(ReadResourceResult {
+                contents: vec![contents],
+            }),
+            None => Err(McpError::resource_not_found(
+                format!("Unknown resource: {}", request.uri),
+                Some(serde_json::json!({ "uri": request.uri })),
+            )),
         }
     }
 
+    async fn list_prompts(
+        &self,
+        _request: Option<rmcp::model::PaginatedRequestParam>,
+        _context: rmcp::service::RequestContext<rmcp::service::RoleServer>,
+    ) -> Result<ListPromptsResult, McpError> {
+        Ok(ListPromptsResult {
+            prompts: prompts::list(),
+            next_cursor: None,
+        })
+    }
+
+    async fn get_prompt(
+        &self,
+        request: GetPromptRequestParam,
+        _context: rmcp::service::RequestContext<rmcp::service::RoleServer>,
+    ) -> Result<GetPromptResult, McpError> {
+        prompts::get(&request.name, request.arguments.as_ref())
+    }
+
+    async fn set_level(
+        &self,
+        request: SetLevelRequestParam,
+        _context: rmcp::service::RequestContext<rmcp::service::RoleServer>,
+    ) -> Result<(), McpError> {
+        log::info!("Client set logging level to {:?}", request.level);
+        *self.session.log_level.lock().await = request.level;
+        Ok(())
+    }
+
+    async fn on_initialized(
+        &self,
+        context: NotificationContext<RoleServer>,
+    ) {
+        let peer = context.peer;
+        let id = self.shared.peers.lock().await.register(peer.clone());
+        let shared = self.shared.clone();
+        tokio::spawn(async move {
+            while !peer.is_transport_closed() {
+                tokio::time::sleep(SESSION_POLL_INTERVAL).await;
+            }
+            shared.peers.lock().await.peers.remove(&id);
+            log::info!("Session {} ended", id);
+        });
+    }
+
+    async fn on_roots_list_changed(
+        &self,
+        _context: NotificationContext<RoleServer>,
+    ) {
+        let count = self.session.roots_list_changed.fetch_add(1, Ordering::SeqCst) + 1;
+        log::info!("Received roots/list_changed notification ({} so far)", count);
+    }
+
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
-            protocol_version: ProtocolVersion::new(0, 1, 0),
-            capabilities: ServerCapabilities::default(),
-            server_info: rmcp::model::ServerInfo {
+            protocol_version: self.shared.protocol_versions.latest(),
+            capabilities: ServerCapabilities::builder()
+                .enable_tools()
+                .enable_tool_list_changed()
+                .enable_resources()
+                .enable_prompts()
+                .enable_logging()
+                .build(),
+            server_info: Implementation {
                 name: "Test Server".into(),
+                title: None,
                 version: "0.1.0".into(),
+                icons: None,
+                website_url: None,
             },
             instructions: None,
         }
     }
 }
 
+/// How the server is exposed, chosen with `MCP_SERVER_TRANSPORT`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TransportMode {
+    /// Streamable HTTP on `MCP_SERVER_HOST`:`MCP_SERVER_PORT` (default).
+    Http,
+    /// JSON-RPC over stdin/stdout, for clients that spawn the server as a child process.
+    Stdio,
+    /// Legacy 2024-11-05 HTTP+SSE only: `GET /sse` for the event stream, `POST /message` for requests.
+    Sse,
+}
+
+impl TransportMode {
+    fn from_env() -> Result<Self, String> {
+        match std::env::var("MCP_SERVER_TRANSPORT").as_deref() {
+            Err(_) | Ok("http") | Ok("streamable-http") => Ok(TransportMode::Http),
+            Ok("stdio") => Ok(TransportMode::Stdio),
+            Ok("sse") => Ok(TransportMode::Sse),
+            Ok(other) => Err(format!(
+                "Invalid MCP_SERVER_TRANSPORT {:?}, expected \"http\", \"stdio\" or \"sse\"",
+                other
+            )),
+        }
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Configure logging
+    // Configure logging. env_logger writes to stderr, which keeps stdout free
+    // for the protocol in stdio mode.
     env_logger::init();
-    log::info!("Starting MCP server...");
+    let mode = TransportMode::from_env()?;
+    log::info!("Starting MCP server ({:?} transport)...", mode);
 
     // Create server instance
-    let server = TestServer::new();
+    let pagination = ToolPagination::from_env();
+    if pagination.generated_tools > 0 || pagination.page_size.is_some() {
+        log::info!(
+            "Tool pagination: {} generated tools, page size {:?}",
+            pagination.generated_tools,
+            pagination.page_size
+        );
+    }
+    let protocol_versions = ProtocolVersions::from_env()?;
+    log::info!("Protocol versions: {}", protocol_versions.supported().join(", "));
+    let server = TestServer::new(pagination, protocol_versions);
+
+    match mode {
+        TransportMode::Http => serve_http(server).await,
+        TransportMode::Stdio => serve_stdio(server).await,
+        TransportMode::Sse => serve_sse(server).await,
+    }
+}
+
+fn server_host() -> String {
+    std::env::var("MCP_SERVER_HOST").unwrap_or_else(|_| "0.0.0.0".to_string())
+}
+
+fn server_port() -> u16 {
+    std::env::var("MCP_SERVER_PORT")
+        .unwrap_or_else(|_| "8000".to_string())
+        .parse::<u16>()
+        .expect("Invalid port number")
+}
+
+/// Serves streamable HTTP on `/mcp` until Ctrl-C, with a fresh
+/// [`TestServer::new_session`] for every session the client opens.
+async fn serve_http(server: TestServer) -> Result<(), Box<dyn std::error::Error>> {
+    let service = StreamableHttpService::new(
+        move || Ok(server.new_session()),
+        Arc::new(LocalSessionManager::default()),
+        StreamableHttpServerConfig::default(),
+    );
+    let router = axum::Router::new().nest_service("/mcp", service);
+    let listener = tokio::net::TcpListener::bind(format!("{}:{}", server_host(), server_port())).await?;
 
-    // Create streamable HTTP server
-    let transport = StreamableHttpService::new(
-        std::env::var("MCP_SERVER_HOST").unwrap_or_else(|_| "0.0.0.0".to_string()),
-        std::env::var("MCP_SERVER_PORT")
-            .unwrap_or_else(|_| "8000".to_string())
-            .parse::<u16>()
-            .expect("Invalid port number"),
-    )?;
+    log::info!("Server listening on {} (/mcp)", listener.local_addr()?);
+    axum::serve(listener, router)
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await?;
+
+    Ok(())
+}
 
-    // Start server
-    log::info!("Server listening on {}", transport.addr());
-    server.serve(transport).await?;
+/// Serves a single session over stdin/stdout until the client closes stdin.
+async fn serve_stdio(server: TestServer) -> Result<(), Box<dyn std::error::Error>> {
+    let service = server.new_session().serve(rmcp::transport::stdio()).await?;
+    log::info!("Serving on stdio");
+    let reason = service.waiting().await?;
+    log::info!("Stdio session ended: {:?}", reason);
 
     Ok(())
-} 
\ No newline at end of file
+}
+
+/// Serves the legacy HTTP+SSE transport, one session per `GET /sse`, until Ctrl-C.
+///
+/// Nothing else is routed, so a streamable HTTP `POST` is rejected with `404`
+/// or `405` and clients that implement the spec's fallback switch to SSE.
+async fn serve_sse(server: TestServer) -> Result<(), Box<dyn std::error::Error>> {
+    let addr: std::net::SocketAddr = format!("{}:{}", server_host(), server_port()).parse()?;
+    let sse_server = SseServer::serve_with_config(SseServerConfig {
+        bind: addr,
+        sse_path: "/sse".to_string(),
+        post_path: "/message".to_string(),
+        ct: CancellationToken::new(),
+        sse_keep_alive: None,
+    })
+    .await?;
+
+    log::info!("SSE server listening on {} (GET /sse, POST /message)", addr);
+    let ct = sse_server.with_service(move || server.new_session());
+    tokio::signal::ctrl_c().await?;
+    ct.cancel();
+
+    Ok(())
+}
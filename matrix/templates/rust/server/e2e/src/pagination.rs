@@ -0,0 +1,73 @@
+//! Cursor-based pagination for list endpoints.
+//!
+//! Cursors are opaque to clients: a base64-encoded offset with a prefix so
+//! that arbitrary strings are rejected rather than misread as positions.
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use rmcp::ErrorData as McpError;
+
+const CURSOR_PREFIX: &str = "offset:";
+
+/// Pagination settings for `tools/list`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ToolPagination {
+    /// Number of `generated_tool_NNN` tools appended to the fixture tools.
+    pub generated_tools: usize,
+    /// Maximum tools per page; `None` returns everything in one page.
+    pub page_size: Option<usize>,
+}
+
+impl ToolPagination {
+    /// Reads `MCP_SERVER_GENERATED_TOOLS` and `MCP_SERVER_TOOL_PAGE_SIZE`.
+    /// Both default to off; a page size of 0 also disables paging.
+    pub fn from_env() -> Self {
+        let parse = |key: &str| {
+            std::env::var(key)
+                .ok()
+                .map(|v| v.parse::<usize>().unwrap_or_else(|_| panic!("Invalid {}", key)))
+        };
+        Self {
+            generated_tools: parse("MCP_SERVER_GENERATED_TOOLS").unwrap_or(0),
+            page_size: parse("MCP_SERVER_TOOL_PAGE_SIZE").filter(|size| *size > 0),
+        }
+    }
+}
+
+pub fn encode_cursor(offset: usize) -> String {
+    URL_SAFE_NO_PAD.encode(format!("{}{}", CURSOR_PREFIX, offset))
+}
+
+pub fn decode_cursor(cursor: &str) -> Result<usize, McpError> {
+    URL_SAFE_NO_PAD
+        .decode(cursor)
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .and_then(|decoded| decoded.strip_prefix(CURSOR_PREFIX)?.parse().ok())
+        .ok_or_else(|| {
+            McpError::invalid_params(
+                "Invalid cursor",
+                Some(serde_json::json!({ "cursor": cursor })),
+            )
+        })
+}
+
+/// Returns the page starting at `cursor` and the cursor for the page after it.
+pub fn paginate<T>(
+    items: Vec<T>,
+    cursor: Option<&str>,
+    page_size: Option<usize>,
+) -> Result<(Vec<T>, Option<String>), McpError> {
+    let offset = cursor.map(decode_cursor).transpose()?.unwrap_or(0);
+    if offset > items.len() {
+        return Err(McpError::invalid_params(
+            "Cursor is past the end of the list",
+            Some(serde_json::json!({ "cursor": cursor })),
+        ));
+    }
+
+    let total = items.len();
+    let end = page_size.map_or(total, |size| offset.saturating_add(size).min(total));
+    let page = items.into_iter().skip(offset).take(end - offset).collect();
+    let next_cursor = (end < total).then(|| encode_cursor(end));
+    Ok((page, next_cursor))
+}
@@ -0,0 +1,134 @@
+//! Prompt fixtures exposed by `TestServer`.
+//!
+//! The set covers the argument shapes clients must handle: no arguments, a
+//! required argument, an optional argument with a default, and a conversation
+//! spanning several messages that embeds a resource.
+
+use rmcp::{
+    model::{
+        AnnotateAble, GetPromptResult, JsonObject, Prompt, PromptArgument, PromptMessage, PromptMessageContent,
+        PromptMessageRole, RawEmbeddedResource,
+    },
+    ErrorData as McpError,
+};
+
+use crate::resources;
+
+pub const SIMPLE: &str = "simple_prompt";
+pub const GREETING: &str = "greeting_prompt";
+pub const STYLED: &str = "styled_prompt";
+pub const CONVERSATION: &str = "conversation_prompt";
+
+pub const DEFAULT_STYLE: &str = "concise";
+
+pub fn list() -> Vec<Prompt> {
+    vec![
+        Prompt::new(SIMPLE, Some("A prompt without arguments"), None),
+        Prompt::new(
+            GREETING,
+            Some("Greets the given name"),
+            Some(vec![argument("name", "Name to greet", true)]),
+        ),
+        Prompt::new(
+            STYLED,
+            Some("Explains a topic in an optional style"),
+            Some(vec![
+                argument("topic", "Topic to explain", true),
+                argument("style", "Writing style, defaults to \"concise\"", false),
+            ]),
+        ),
+        Prompt::new(
+            CONVERSATION,
+            Some("A multi-message conversation that embeds a resource"),
+            None,
+        ),
+    ]
+}
+
+pub fn get(name: &str, arguments: Option<&JsonObject>) -> Result<GetPromptResult, McpError> {
+    match name {
+        SIMPLE => Ok(GetPromptResult {
+            description: Some("A prompt without arguments".to_string()),
+            messages: vec![PromptMessage::new_text(
+                PromptMessageRole::User,
+                "This is a simple prompt without arguments.",
+            )],
+        }),
+        GREETING => {
+            let name = required_argument(arguments, "name")?;
+            Ok(GetPromptResult {
+                description: Some("Greets the given name".to_string()),
+                messages: vec![PromptMessage::new_text(
+                    PromptMessageRole::User,
+                    format!("Hello, {}!", name),
+                )],
+            })
+        }
+        STYLED => {
+            let topic = required_argument(arguments, "topic")?;
+            let style = optional_argument(arguments, "style")?.unwrap_or(DEFAULT_STYLE);
+            Ok(GetPromptResult {
+                description: Some("Explains a topic in an optional style".to_string()),
+                messages: vec![PromptMessage::new_text(
+                    PromptMessageRole::User,
+                    format!("Explain {} in a {} style.", topic, style),
+                )],
+            })
+        }
+        CONVERSATION => {
+            let greeting = resources::read(resources::GREETING_URI).expect("greeting fixture is static");
+            Ok(GetPromptResult {
+                description: Some("A multi-message conversation that embeds a resource".to_string()),
+                messages: vec![
+                    PromptMessage::new_text(PromptMessageRole::User, "What does the greeting resource say?"),
+                    PromptMessage::new_text(PromptMessageRole::Assistant, "Here is the greeting resource:"),
+                    PromptMessage {
+                        role: PromptMessageRole::Assistant,
+                        content: PromptMessageContent::Resource {
+                            resource: RawEmbeddedResource {
+                                meta: None,
+                                resource: greeting,
+                            }
+                            .no_annotation(),
+                        },
+                    },
+                    PromptMessage::new_text(PromptMessageRole::User, "Thanks!"),
+                ],
+            })
+        }
+        _ => Err(McpError::invalid_params(
+            format!("Unknown prompt: {}", name),
+            Some(serde_json::json!({ "name": name })),
+        )),
+    }
+}
+
+fn argument(name: &str, description: &str, required: bool) -> PromptArgument {
+    PromptArgument {
+        name: name.to_string(),
+        title: None,
+        description: Some(description.to_string()),
+        required: Some(required),
+    }
+}
+
+fn required_argument<'a>(arguments: Option<&'a JsonObject>, key: &str) -> Result<&'a str, McpError> {
+    optional_argument(arguments, key)?.ok_or_else(|| {
+        McpError::invalid_params(
+            format!("Missing required argument: {}", key),
+            Some(serde_json::json!({ "argument": key })),
+        )
+    })
+}
+
+fn optional_argument<'a>(arguments: Option<&'a JsonObject>, key: &str) -> Result<Option<&'a str>, McpError> {
+    match arguments.and_then(|args| args.get(key)) {
+        None => Ok(None),
+        Some(value) => value.as_str().map(Some).ok_or_else(|| {
+            McpError::invalid_params(
+                format!("Argument {} must be a string", key),
+                Some(serde_json::json!({ "argument": key })),
+            )
+        }),
+    }
+}
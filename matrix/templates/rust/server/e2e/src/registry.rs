@@ -0,0 +1,207 @@
+//! Declarative tool registry.
+//!
+//! Each fixture tool is declared once as a [`ToolDef`] holding its metadata and
+//! handler. `tools/list` and `tools/call` are both derived from the registry, so
+//! the advertised schema and the dispatch table cannot drift apart. Arguments
+//! are validated against the advertised `inputSchema` before a handler runs.
+
+use std::{collections::HashMap, future::Future, pin::Pin, sync::Arc};
+
+use jsonschema::{error::ValidationErrorKind, ValidationError, Validator};
+use rmcp::{
+    model::{CallToolResult, JsonObject, Tool, ToolAnnotations},
+    service::{RequestContext, RoleServer},
+    ErrorData as McpError,
+};
+
+use crate::TestServer;
+
+pub type ToolFuture = Pin<Box<dyn Future<Output = Result<CallToolResult, McpError>> + Send>>;
+
+/// Handler invoked with a clone of the server, the call arguments (an empty
+/// object when the client sent none) and the request context.
+pub type ToolHandler = fn(TestServer, JsonObject, RequestContext<RoleServer>) -> ToolFuture;
+
+pub struct ToolDef {
+    tool: Tool,
+    handler: ToolHandler,
+    validator: Validator,
+}
+
+impl ToolDef {
+    /// A tool taking no arguments; chain the setters below to describe more.
+    pub fn new(name: &'static str, description: &'static str, handler: ToolHandler) -> Self {
+        let schema = serde_json::json!({
+            "type": "object",
+            "properties": {}
+        });
+        Self {
+            validator: compile(name, &schema),
+            tool: Tool::new(name, description, Arc::new(object(schema))),
+            handler,
+        }
+    }
+
+    pub fn input_schema(mut self, schema: serde_json::Value) -> Self {
+        self.validator = compile(&self.tool.name, &schema);
+        self.tool.input_schema = Arc::new(object(schema));
+        self
+    }
+
+    pub fn output_schema(mut self, schema: serde_json::Value) -> Self {
+        self.tool.output_schema = Some(Arc::new(object(schema)));
+        self
+    }
+
+    pub fn annotations(mut self, annotations: ToolAnnotations) -> Self {
+        self.tool.annotations = Some(annotations);
+        self
+    }
+
+    /// Validates `arguments` against the input schema, then runs the handler.
+    pub async fn call(
+        &self,
+        server: TestServer,
+        arguments: Option<JsonObject>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let arguments = arguments.unwrap_or_default();
+        self.validate(&arguments)?;
+        (self.handler)(server, arguments, context).await
+    }
+
+    fn validate(&self, arguments: &JsonObject) -> Result<(), McpError> {
+        validate_with(&self.tool.name, &self.validator, arguments)
+    }
+}
+
+/// A compiled input schema for tools built outside the registry, such as the
+/// tools registered at runtime, giving them the same checks and errors as
+/// [`ToolDef::call`].
+pub struct InputValidator(Validator);
+
+impl InputValidator {
+    pub fn new(schema: &JsonObject) -> Self {
+        Self(compile("<runtime>", &serde_json::Value::Object(schema.clone())))
+    }
+
+    pub fn validate(&self, tool: &str, arguments: &JsonObject) -> Result<(), McpError> {
+        validate_with(tool, &self.0, arguments)
+    }
+}
+
+/// Returns `invalid_params` listing every violation, each with a JSON
+/// pointer to the offending field.
+fn validate_with(name: &str, validator: &Validator, arguments: &JsonObject) -> Result<(), McpError> {
+    let instance = serde_json::Value::Object(arguments.clone());
+    let errors: Vec<_> = validator
+        .iter_errors(&instance)
+        .map(|error| {
+            serde_json::json!({
+                "path": field_path(&error),
+                "message": error.to_string(),
+            })
+        })
+        .collect();
+    if errors.is_empty() {
+        return Ok(());
+    }
+
+    let first = &errors[0];
+    Err(McpError::invalid_params(
+        format!(
+            "Invalid arguments for tool {}: {} at {}",
+            name,
+            first["message"].as_str().unwrap_or_default(),
+            first["path"].as_str().unwrap_or_default(),
+        ),
+        Some(serde_json::json!({
+            "tool": name,
+            "errors": errors,
+        })),
+    ))
+}
+
+/// Fixture tools in declaration order, which is also the `tools/list` order.
+#[derive(Default)]
+pub struct ToolRegistry {
+    tools: Vec<ToolDef>,
+    index: HashMap<String, usize>,
+}
+
+impl ToolRegistry {
+    /// Adds a tool. Panics if the name is already taken, since that is a
+    /// mistake in the fixture declarations rather than a runtime condition.
+    pub fn register(&mut self, def: ToolDef) -> &mut Self {
+        let name = def.tool.name.to_string();
+        assert!(
+            !self.index.contains_key(&name),
+            "tool {} registered twice",
+            name
+        );
+        self.index.insert(name, self.tools.len());
+        self.tools.push(def);
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&ToolDef> {
+        self.index.get(name).map(|&i| &self.tools[i])
+    }
+
+    pub fn tools(&self) -> impl Iterator<Item = Tool> + '_ {
+        self.tools.iter().map(|def| def.tool.clone())
+    }
+}
+
+/// Reads a string argument, if present and a string.
+pub fn str_arg<'a>(arguments: &'a JsonObject, key: &str) -> Option<&'a str> {
+    arguments.get(key).and_then(|v| v.as_str())
+}
+
+/// Reads a non-negative integer argument, if present and an integer.
+pub fn u64_arg(arguments: &JsonObject, key: &str) -> Option<u64> {
+    arguments.get(key).and_then(|v| v.as_u64())
+}
+
+/// Reads an integer argument that must fit in an `i64`. The JSON Schema
+/// `integer` type has no bounds, so validation alone lets larger values through;
+/// those are rejected with the same `invalid_params` shape as [`validate`].
+pub fn i64_arg(tool: &str, arguments: &JsonObject, key: &str) -> Result<Option<i64>, McpError> {
+    let Some(value) = arguments.get(key) else {
+        return Ok(None);
+    };
+    value.as_i64().map(Some).ok_or_else(|| {
+        let message = format!("{} is not a 64-bit signed integer", value);
+        McpError::invalid_params(
+            format!("Invalid arguments for tool {}: {} at /{}", tool, message, key),
+            Some(serde_json::json!({
+                "tool": tool,
+                "errors": [{ "path": format!("/{}", key), "message": message }],
+            })),
+        )
+    })
+}
+
+/// JSON pointer to the field an error is about. A missing required property is
+/// reported by the validator at its parent object, so append the property name.
+fn field_path(error: &ValidationError) -> String {
+    let path = error.instance_path.to_string();
+    match &error.kind {
+        ValidationErrorKind::Required { property } => {
+            format!("{}/{}", path, property.as_str().unwrap_or_default())
+        }
+        _ => path,
+    }
+}
+
+fn compile(name: &str, schema: &serde_json::Value) -> Validator {
+    jsonschema::validator_for(schema)
+        .unwrap_or_else(|e| panic!("tool {} has an invalid input schema: {}", name, e))
+}
+
+fn object(schema: serde_json::Value) -> JsonObject {
+    match schema {
+        serde_json::Value::Object(object) => object,
+        other => panic!("tool schema must be a JSON object, got {}", other),
+    }
+}
@@ -0,0 +1,114 @@
+//! Deterministic resource fixtures exposed by `TestServer`.
+//!
+//! Every client in the matrix reads these back and compares them byte-for-byte,
+//! so the payloads come from `mcpbench_contract::fixtures`, which the checker
+//! compares against.
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use rmcp::model::{
+    AnnotateAble, RawResource, RawResourceTemplate, Resource, ResourceContents, ResourceTemplate,
+};
+
+pub use mcpbench_contract::fixtures::{
+    fixture_bytes, pattern_bytes, BYTES_URI, ECHO_TEMPLATE, GREETING_TEXT, GREETING_URI, PATTERN_TEMPLATE,
+    README_TEXT, README_URI,
+};
+
+pub const ECHO_PREFIX: &str = "test://echo/";
+pub const PATTERN_PREFIX: &str = "test://pattern/";
+
+/// Upper bound on `test://pattern/{length}` so a client cannot ask for an unbounded blob.
+pub const MAX_PATTERN_LENGTH: usize = 65536;
+
+pub fn list() -> Vec<Resource> {
+    vec![
+        RawResource {
+            uri: GREETING_URI.to_string(),
+            name: "greeting".to_string(),
+            title: None,
+            description: Some("Plain text greeting".to_string()),
+            mime_type: Some("text/plain".to_string()),
+            size: Some(GREETING_TEXT.len() as u32),
+            icons: None,
+        }
+        .no_annotation(),
+        RawResource {
+            uri: README_URI.to_string(),
+            name: "readme".to_string(),
+            title: None,
+            description: Some("Markdown document".to_string()),
+            mime_type: Some("text/markdown".to_string()),
+            size: Some(README_TEXT.len() as u32),
+            icons: None,
+        }
+        .no_annotation(),
+        RawResource {
+            uri: BYTES_URI.to_string(),
+            name: "bytes".to_string(),
+            title: None,
+            description: Some("All 256 byte values in ascending order".to_string()),
+            mime_type: Some("application/octet-stream".to_string()),
+            size: Some(256),
+            icons: None,
+        }
+        .no_annotation(),
+    ]
+}
+
+pub fn templates() -> Vec<ResourceTemplate> {
+    vec![
+        RawResourceTemplate {
+            uri_template: ECHO_TEMPLATE.to_string(),
+            name: "echo".to_string(),
+            title: None,
+            description: Some("Returns the `text` path segment as a text resource".to_string()),
+            mime_type: Some("text/plain".to_string()),
+        }
+        .no_annotation(),
+        RawResourceTemplate {
+            uri_template: PATTERN_TEMPLATE.to_string(),
+            name: "pattern".to_string(),
+            title: None,
+            description: Some("Returns `length` bytes where byte i is i % 256".to_string()),
+            mime_type: Some("application/octet-stream".to_string()),
+        }
+        .no_annotation(),
+    ]
+}
+
+/// Resolves a static or templated URI. `None` means the URI is unknown.
+pub fn read(uri: &str) -> Option<ResourceContents> {
+    match uri {
+        GREETING_URI => Some(text(uri, "text/plain", GREETING_TEXT)),
+        README_URI => Some(text(uri, "text/markdown", README_TEXT)),
+        BYTES_URI => Some(blob(uri, "application/octet-stream", &fixture_bytes())),
+        _ => {
+            if let Some(echoed) = uri.strip_prefix(ECHO_PREFIX) {
+                return Some(text(uri, "text/plain", echoed));
+            }
+            let length = uri.strip_prefix(PATTERN_PREFIX)?.parse::<usize>().ok()?;
+            if length > MAX_PATTERN_LENGTH {
+                return None;
+            }
+            Some(blob(uri, "application/octet-stream", &pattern_bytes(length)))
+        }
+    }
+}
+
+fn text(uri: &str, mime_type: &str, text: &str) -> ResourceContents {
+    ResourceContents::TextResourceContents {
+        uri: uri.to_string(),
+        mime_type: Some(mime_type.to_string()),
+        text: text.to_string(),
+        meta: None,
+    }
+}
+
+fn blob(uri: &str, mime_type: &str, bytes: &[u8]) -> ResourceContents {
+    ResourceContents::BlobResourceContents {
+        uri: uri.to_string(),
+        mime_type: Some(mime_type.to_string()),
+        blob: STANDARD.encode(bytes),
+        meta: None,
+    }
+}
@@ -0,0 +1,416 @@
+//! Fixture tool declarations.
+//!
+//! To add a conformance tool, implement its behaviour on `TestServer` and
+//! register it here with its schema; listing and dispatch pick it up.
+
+use rmcp::{
+    model::{CallToolResult, ToolAnnotations},
+    ErrorData as McpError,
+};
+use serde_json::json;
+
+use crate::{
+    content,
+    registry::{i64_arg, str_arg, u64_arg, ToolDef, ToolRegistry},
+};
+
+/// Annotations for tools that only read fixture data.
+fn read_only() -> ToolAnnotations {
+    ToolAnnotations {
+        title: None,
+        read_only_hint: Some(true),
+        destructive_hint: Some(false),
+        idempotent_hint: Some(true),
+        open_world_hint: Some(false),
+    }
+}
+
+/// Annotations for tools that change the server's tool set.
+fn admin(destructive: bool) -> ToolAnnotations {
+    ToolAnnotations {
+        title: None,
+        read_only_hint: Some(false),
+        destructive_hint: Some(destructive),
+        idempotent_hint: Some(true),
+        open_world_hint: Some(false),
+    }
+}
+
+pub fn registry() -> ToolRegistry {
+    let mut registry = ToolRegistry::default();
+
+    registry
+        .register(
+            ToolDef::new("send_message", "Send a message to the server", |server, args, _| {
+                Box::pin(async move {
+                    let message = str_arg(&args, "message").unwrap_or("No message").to_string();
+                    server.handle_message(message).await
+                })
+            })
+            .input_schema(json!({
+                "type": "object",
+                "properties": {
+                    "message": {
+                        "type": "string",
+                        "description": "Message to send"
+                    }
+                },
+                "required": ["message"]
+            })),
+        )
+        .register(
+            ToolDef::new("get_server_info", "Get server information and status", |server, _, context| {
+                Box::pin(async move { server.get_server_info(&context.peer).await })
+            })
+            .annotations(read_only()),
+        )
+        .register(
+            ToolDef::new(
+                "increment",
+                "Add a value to this session's counter and return the new total",
+                |server, args, _| {
+                    Box::pin(async move {
+                        let value = i64_arg("increment", &args, "value")?.unwrap_or(1);
+                        server.increment(value).await
+                    })
+                },
+            )
+            .input_schema(json!({
+                "type": "object",
+                "properties": {
+                    "value": {
+                        "type": "integer",
+                        "description": "Amount to add to the counter",
+                        "default": 1
+                    }
+                }
+            }))
+            .annotations(ToolAnnotations {
+                title: None,
+                read_only_hint: Some(false),
+                destructive_hint: Some(false),
+                idempotent_hint: Some(false),
+                open_world_hint: Some(false),
+            }),
+        )
+        .register(
+            ToolDef::new(
+                "sample_llm",
+                "Ask the client to sample an LLM completion and return it",
+                |server, args, context| {
+                    Box::pin(async move {
+                        let prompt = str_arg(&args, "prompt").unwrap_or_default().to_string();
+                        let max_tokens = u64_arg(&args, "max_tokens").unwrap_or(100) as u32;
+                        server.sample_llm(&context.peer, prompt, max_tokens).await
+                    })
+                },
+            )
+            .input_schema(json!({
+                "type": "object",
+                "properties": {
+                    "prompt": {
+                        "type": "string",
+                        "description": "Prompt to send to the client's LLM"
+                    },
+                    "max_tokens": {
+                        "type": "integer",
+                        "description": "Maximum tokens to generate",
+                        "default": 100
+                    }
+                },
+                "required": ["prompt"]
+            })),
+        )
+        .register(
+            ToolDef::new(
+                "list_roots",
+                "Ask the client for its roots and report what was returned",
+                |server, _, context| Box::pin(async move { server.list_roots(&context.peer).await }),
+            )
+            .annotations(read_only()),
+        )
+        .register(ToolDef::new(
+            "elicit_user_info",
+            "Ask the client to fill in a user information form",
+            |server, _, context| Box::pin(async move { server.elicit_user_info(&context.peer).await }),
+        ))
+        .register(
+            ToolDef::new(
+                "long_running_operation",
+                "Run for a while, reporting progress when a progress token is supplied",
+                |server, args, context| {
+                    Box::pin(async move {
+                        let steps = u64_arg(&args, "steps").unwrap_or(5) as u32;
+                        let duration_ms = u64_arg(&args, "duration_ms").unwrap_or(500);
+                        let progress_token = context.meta.get_progress_token();
+                        server
+                            .long_running_operation(&context.peer, progress_token, steps, duration_ms)
+                            .await
+                    })
+                },
+            )
+            .input_schema(json!({
+                "type": "object",
+                "properties": {
+                    "steps": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "Number of progress notifications to send",
+                        "default": 5
+                    },
+                    "duration_ms": {
+                        "type": "integer",
+                        "minimum": 0,
+                        "description": "Total duration of the operation in milliseconds",
+                        "default": 500
+                    }
+                }
+            })),
+        )
+        .register(
+            ToolDef::new(
+                "slow_operation",
+                "Sleep for a while, stopping early if the request is cancelled",
+                |server, args, context| {
+                    Box::pin(async move {
+                        let operation_id = str_arg(&args, "operation_id").unwrap_or("default").to_string();
+                        let duration_ms = u64_arg(&args, "duration_ms").unwrap_or(5000);
+                        server.slow_operation(&context.ct, &context.peer, operation_id, duration_ms).await
+                    })
+                },
+            )
+            .input_schema(json!({
+                "type": "object",
+                "properties": {
+                    "operation_id": {
+                        "type": "string",
+                        "description": "Identifier used to query the outcome with get_operation_status"
+                    },
+                    "duration_ms": {
+                        "type": "integer",
+                        "minimum": 0,
+                        "description": "How long the operation runs if not cancelled",
+                        "default": 5000
+                    }
+                },
+                "required": ["operation_id"]
+            })),
+        )
+        .register(
+            ToolDef::new(
+                "get_operation_status",
+                "Report whether a slow_operation is running, completed or cancelled",
+                |server, args, _| {
+                    Box::pin(async move {
+                        let operation_id = str_arg(&args, "operation_id").unwrap_or("default").to_string();
+                        server.get_operation_status(operation_id).await
+                    })
+                },
+            )
+            .input_schema(json!({
+                "type": "object",
+                "properties": {
+                    "operation_id": {
+                        "type": "string",
+                        "description": "Identifier passed to slow_operation"
+                    }
+                },
+                "required": ["operation_id"]
+            }))
+            .annotations(read_only()),
+        )
+        .register(ToolDef::new(
+            "emit_log_messages",
+            "Send one notifications/message per logging level at or above the current level",
+            |server, _, context| Box::pin(async move { server.emit_log_messages(&context.peer).await }),
+        ))
+        .register(
+            ToolDef::new(
+                "get_weather",
+                "Return fixed weather data as structured content",
+                |server, args, _| {
+                    Box::pin(async move {
+                        let city = str_arg(&args, "city").unwrap_or_default().to_string();
+                        server.get_weather(city).await
+                    })
+                },
+            )
+            .input_schema(json!({
+                "type": "object",
+                "properties": {
+                    "city": {
+                        "type": "string",
+                        "description": "City name"
+                    }
+                },
+                "required": ["city"]
+            }))
+            .output_schema(json!({
+                "type": "object",
+                "properties": {
+                    "city": { "type": "string" },
+                    "temperature": { "type": "number", "description": "Temperature in Celsius" },
+                    "conditions": { "type": "string" },
+                    "humidity": { "type": "integer", "minimum": 0, "maximum": 100 }
+                },
+                "required": ["city", "temperature", "conditions", "humidity"],
+                "additionalProperties": false
+            }))
+            .annotations(read_only()),
+        )
+        .register(
+            ToolDef::new(
+                "calculate_stats",
+                "Compute summary statistics as structured content",
+                |server, args, _| {
+                    Box::pin(async move {
+                        let numbers = args
+                            .get("numbers")
+                            .and_then(|v| v.as_array())
+                            .into_iter()
+                            .flatten()
+                            .filter_map(|v| v.as_f64())
+                            .collect();
+                        server.calculate_stats(numbers).await
+                    })
+                },
+            )
+            .input_schema(json!({
+                "type": "object",
+                "properties": {
+                    "numbers": {
+                        "type": "array",
+                        "items": { "type": "number" },
+                        "minItems": 1,
+                        "description": "Numbers to summarize"
+                    }
+                },
+                "required": ["numbers"]
+            }))
+            .output_schema(json!({
+                "type": "object",
+                "properties": {
+                    "count": { "type": "integer", "minimum": 1 },
+                    "sum": { "type": "number" },
+                    "mean": { "type": "number" },
+                    "min": { "type": "number" },
+                    "max": { "type": "number" }
+                },
+                "required": ["count", "sum", "mean", "min", "max"],
+                "additionalProperties": false
+            }))
+            .annotations(read_only()),
+        )
+        .register(
+            ToolDef::new("get_image", "Return a 2x2 PNG image", |_, _, _| {
+                Box::pin(async move { Ok(CallToolResult::success(vec![content::image()])) })
+            })
+            .annotations(read_only()),
+        )
+        .register(
+            ToolDef::new("get_audio", "Return a short WAV audio clip", |_, _, _| {
+                Box::pin(async move { Ok(CallToolResult::success(vec![content::audio()])) })
+            })
+            .annotations(read_only()),
+        )
+        .register(
+            ToolDef::new(
+                "get_embedded_resource",
+                "Return an embedded text or blob resource",
+                |_, args, _| {
+                    Box::pin(async move {
+                        match str_arg(&args, "kind").unwrap_or("text") {
+                            "text" => Ok(CallToolResult::success(vec![content::embedded_text()])),
+                            "blob" => Ok(CallToolResult::success(vec![content::embedded_blob()])),
+                            kind => Err(McpError::invalid_params(
+                                format!("kind must be \"text\" or \"blob\", got {:?}", kind),
+                                Some(json!({ "argument": "kind" })),
+                            )),
+                        }
+                    })
+                },
+            )
+            .input_schema(json!({
+                "type": "object",
+                "properties": {
+                    "kind": {
+                        "type": "string",
+                        "enum": ["text", "blob"],
+                        "description": "Which fixture resource to embed",
+                        "default": "text"
+                    }
+                }
+            }))
+            .annotations(read_only()),
+        )
+        .register(
+            ToolDef::new("get_resource_link", "Return a link to a fixture resource", |_, _, _| {
+                Box::pin(async move { Ok(CallToolResult::success(vec![content::resource_link()])) })
+            })
+            .annotations(read_only()),
+        )
+        .register(
+            ToolDef::new(
+                "get_mixed_content",
+                "Return text, image, audio, embedded resource and resource link blocks with annotations",
+                |_, _, _| Box::pin(async move { Ok(CallToolResult::success(content::mixed())) }),
+            )
+            .annotations(read_only()),
+        )
+        .register(
+            ToolDef::new(
+                "add_tool",
+                "Register or update a dynamic tool and notify sessions with tools/list_changed",
+                |server, args, _| {
+                    Box::pin(async move {
+                        let name = str_arg(&args, "name").unwrap_or_default().to_string();
+                        let description = str_arg(&args, "description")
+                            .unwrap_or("Dynamically registered tool")
+                            .to_string();
+                        server.add_tool(name, description).await
+                    })
+                },
+            )
+            .input_schema(json!({
+                "type": "object",
+                "properties": {
+                    "name": {
+                        "type": "string",
+                        "description": "Tool name, must start with \"dynamic_\""
+                    },
+                    "description": {
+                        "type": "string",
+                        "description": "Tool description"
+                    }
+                },
+                "required": ["name"]
+            }))
+            .annotations(admin(false)),
+        )
+        .register(
+            ToolDef::new(
+                "remove_tool",
+                "Unregister a dynamic tool and notify sessions with tools/list_changed",
+                |server, args, _| {
+                    Box::pin(async move {
+                        let name = str_arg(&args, "name").unwrap_or_default().to_string();
+                        server.remove_tool(name).await
+                    })
+                },
+            )
+            .input_schema(json!({
+                "type": "object",
+                "properties": {
+                    "name": {
+                        "type": "string",
+                        "description": "Name of a tool previously added with add_tool"
+                    }
+                },
+                "required": ["name"]
+            }))
+            .annotations(admin(true)),
+        );
+
+    registry
+}
+
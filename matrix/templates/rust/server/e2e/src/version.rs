@@ -0,0 +1,131 @@
+//! Protocol version negotiation.
+//!
+//! The client proposes a version in `initialize`. If the server supports it,
+//! the server answers with the same version; otherwise it counter-offers the
+//! latest version it supports and leaves it to the client to disconnect.
+
+use rmcp::model::{CallToolResult, Content, ProtocolVersion, RawContent, Tool};
+
+/// Every protocol revision this server can speak, oldest first.
+pub const KNOWN_VERSIONS: [&str; 3] = ["2024-11-05", "2025-03-26", "2025-06-18"];
+
+/// First revision with tool annotations.
+const TOOL_ANNOTATIONS_SINCE: &str = "2025-03-26";
+/// First revision with audio content.
+const AUDIO_CONTENT_SINCE: &str = "2025-03-26";
+/// First revision with tool output schemas and structured content.
+const OUTPUT_SCHEMA_SINCE: &str = "2025-06-18";
+/// First revision with `resource_link` content.
+const RESOURCE_LINK_SINCE: &str = "2025-06-18";
+
+/// The protocol versions a server instance accepts, oldest first.
+#[derive(Debug, Clone)]
+pub struct ProtocolVersions {
+    supported: Vec<&'static str>,
+}
+
+impl Default for ProtocolVersions {
+    fn default() -> Self {
+        Self {
+            supported: KNOWN_VERSIONS.to_vec(),
+        }
+    }
+}
+
+impl ProtocolVersions {
+    /// Reads `MCP_SERVER_PROTOCOL_VERSIONS`, a comma-separated subset of
+    /// [`KNOWN_VERSIONS`]. Defaults to all of them.
+    pub fn from_env() -> Result<Self, String> {
+        let Ok(value) = std::env::var("MCP_SERVER_PROTOCOL_VERSIONS") else {
+            return Ok(Self::default());
+        };
+        let mut requested = Vec::new();
+        for version in value.split(',').map(str::trim).filter(|v| !v.is_empty()) {
+            match KNOWN_VERSIONS.iter().find(|known| **known == version) {
+                Some(known) => requested.push(*known),
+                None => {
+                    return Err(format!(
+                        "Invalid MCP_SERVER_PROTOCOL_VERSIONS entry {:?}, expected one of {:?}",
+                        version, KNOWN_VERSIONS
+                    ))
+                }
+            }
+        }
+        if requested.is_empty() {
+            return Err("MCP_SERVER_PROTOCOL_VERSIONS lists no versions".to_string());
+        }
+        // Dates sort lexically, so this orders the versions oldest first
+        requested.sort_unstable();
+        requested.dedup();
+        Ok(Self { supported: requested })
+    }
+
+    pub fn supported(&self) -> &[&'static str] {
+        &self.supported
+    }
+
+    pub fn latest(&self) -> ProtocolVersion {
+        version(self.supported.last().expect("at least one supported version"))
+    }
+
+    /// The version to answer `initialize` with when the client asked for `requested`.
+    pub fn negotiate(&self, requested: &ProtocolVersion) -> ProtocolVersion {
+        let requested = as_str(requested);
+        match self.supported.iter().find(|v| **v == requested) {
+            Some(supported) => version(supported),
+            None => self.latest(),
+        }
+    }
+}
+
+/// Removes the tool fields the negotiated revision does not define.
+pub fn gate_tool(mut tool: Tool, negotiated: &ProtocolVersion) -> Tool {
+    let negotiated = as_str(negotiated);
+    if negotiated.as_str() < TOOL_ANNOTATIONS_SINCE {
+        tool.annotations = None;
+    }
+    if negotiated.as_str() < OUTPUT_SCHEMA_SINCE {
+        tool.output_schema = None;
+    }
+    tool
+}
+
+/// Removes the result fields the negotiated revision does not define and
+/// replaces content blocks it does not define with a text description, so an
+/// older client never sees a shape it cannot parse.
+pub fn gate_result(mut result: CallToolResult, negotiated: &ProtocolVersion) -> CallToolResult {
+    let negotiated = as_str(negotiated);
+    if negotiated.as_str() < OUTPUT_SCHEMA_SINCE {
+        result.structured_content = None;
+    }
+    result.content = result
+        .content
+        .into_iter()
+        .map(|content| match &content.raw {
+            RawContent::Audio(audio) if negotiated.as_str() < AUDIO_CONTENT_SINCE => {
+                Content::text(format!(
+                    "[{} audio omitted: requires protocol {}]",
+                    audio.mime_type, AUDIO_CONTENT_SINCE
+                ))
+            }
+            RawContent::ResourceLink(link) if negotiated.as_str() < RESOURCE_LINK_SINCE => {
+                Content::text(format!("Resource: {}", link.uri))
+            }
+            _ => content,
+        })
+        .collect();
+    result
+}
+
+/// The version string as it appears on the wire.
+pub fn as_str(version: &ProtocolVersion) -> String {
+    match serde_json::to_value(version) {
+        Ok(serde_json::Value::String(version)) => version,
+        other => format!("{:?}", other),
+    }
+}
+
+fn version(version: &str) -> ProtocolVersion {
+    serde_json::from_value(serde_json::Value::String(version.to_string()))
+        .expect("protocol versions deserialize from any string")
+}